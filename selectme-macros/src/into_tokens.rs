@@ -165,6 +165,22 @@ pub(crate) fn string(s: &str) -> impl IntoTokens + '_ {
     StringLiteral(s)
 }
 
+struct Label<'a>(&'a str);
+
+impl IntoTokens for Label<'_> {
+    fn into_tokens(self, stream: &mut TokenStream, span: Span) {
+        let mut punct = Punct::new('\'', Spacing::Joint);
+        punct.set_span(span);
+        stream.push(TokenTree::Punct(punct));
+        self.0.into_tokens(stream, span);
+    }
+}
+
+/// Construct a label `'<name>`.
+pub(crate) fn label(name: &str) -> impl IntoTokens + '_ {
+    Label(name)
+}
+
 impl IntoTokens for &[TokenTree] {
     fn into_tokens(self, stream: &mut TokenStream, _: Span) {
         for tt in self {
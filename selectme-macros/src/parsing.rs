@@ -9,6 +9,7 @@ const BUF: usize = 2;
 pub(crate) const COMMA: [char; 2] = [',', '\0'];
 pub(crate) const EQ: [char; 2] = ['=', '\0'];
 pub(crate) const ROCKET: [char; 2] = ['=', '>'];
+pub(crate) const SEMI: [char; 2] = [';', '\0'];
 
 pub(crate) struct Buf {
     // Static ring buffer used for processing tokens.
@@ -91,6 +92,11 @@ impl Buf {
         self.string.as_str()
     }
 
+    /// Iterate over the tokens currently held by the ring buffer.
+    fn buffered(&self) -> impl Iterator<Item = &TokenTree> + '_ {
+        (self.tail..self.head).flat_map(move |n| self.ring[n % BUF].as_ref())
+    }
+
     /// Test if the given [TokenTree] matches the specified condition.
     pub(crate) fn ident_matches(
         &mut self,
@@ -164,6 +170,43 @@ impl<'a> BaseParser<'a> {
         self.buf.peek2(&mut self.it)
     }
 
+    /// Scan ahead from the `n`th token without consuming anything and return
+    /// the first top-level punctuation out of `candidates` encountered.
+    pub(crate) fn scan_punct(&mut self, n: usize, candidates: &[[char; 2]]) -> Option<[char; 2]> {
+        let mut it = self
+            .buf
+            .buffered()
+            .cloned()
+            .chain(self.it.clone())
+            .skip(n)
+            .peekable();
+
+        while let Some(tt) = it.next() {
+            let p = match tt {
+                TokenTree::Punct(p) => p,
+                _ => continue,
+            };
+
+            let tail = match it.peek() {
+                Some(TokenTree::Punct(next)) if p.spacing() == Spacing::Joint => next.as_char(),
+                _ => '\0',
+            };
+
+            for &[head, expected] in candidates {
+                if head == p.as_char() && (expected == '\0' || expected == tail) {
+                    return Some([head, expected]);
+                }
+            }
+
+            // Skip over the tail of a multi-character punctuation.
+            if tail != '\0' {
+                it.next();
+            }
+        }
+
+        None
+    }
+
     /// Bump the last token.
     pub(crate) fn bump(&mut self) -> Option<TokenTree> {
         if let Some(head) = self.buf.next() {
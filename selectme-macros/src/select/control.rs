@@ -0,0 +1,144 @@
+use proc_macro::{Delimiter, Group, Spacing, Span, TokenTree};
+
+use crate::into_tokens::{braced, label, parens, IntoTokens};
+use crate::tok::S;
+use crate::token_stream::TokenStream;
+
+/// Unlabeled `break` and `continue` expressions found in the handlers of a
+/// select with the `quorum` option.
+///
+/// The handlers are evaluated in a loop over the outputs, so these are
+/// rewritten to record which one was used and leave that loop. They are then
+/// evaluated again in the enclosing scope once the loop has finished.
+#[derive(Default)]
+pub(crate) struct Control {
+    /// If a `break` without a value was found.
+    pub(crate) is_break: bool,
+    /// If a `break <value>` was found.
+    pub(crate) is_break_with: bool,
+    /// If a `continue` was found.
+    pub(crate) is_continue: bool,
+}
+
+impl Control {
+    /// Test if any control flow was found.
+    pub(crate) fn is_used(&self) -> bool {
+        self.is_break || self.is_break_with || self.is_continue
+    }
+
+    /// Rewrite control flow in the given tokens, where `support` is the path to
+    /// the support module, `var` the variable control flow is stored in and
+    /// `name` the label of the loop to leave.
+    pub(crate) fn rewrite<T>(
+        &mut self,
+        tokens: proc_macro::TokenStream,
+        support: T,
+        var: &str,
+        name: &str,
+    ) -> TokenStream
+    where
+        T: Copy + IntoTokens,
+    {
+        let tokens = tokens.into_iter().collect::<Vec<_>>();
+        let mut out = TokenStream::default();
+        let mut it = tokens.iter().enumerate();
+
+        while let Some((n, tt)) = it.next() {
+            match tt {
+                TokenTree::Ident(ident) => {
+                    let ident = ident.to_string();
+
+                    match ident.as_str() {
+                        // Control flow in nested loops and async blocks
+                        // doesn't target the enclosing scope, so their
+                        // bodies are kept as-is.
+                        "loop" | "while" | "for" | "async" => {
+                            out.push(tt.clone());
+
+                            for (_, tt) in it.by_ref() {
+                                out.push(tt.clone());
+
+                                if matches!(tt, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace)
+                                {
+                                    break;
+                                }
+                            }
+
+                            continue;
+                        }
+                        "break" | "continue" if !is_label(tokens.get(n + 1)) => {}
+                        _ => {
+                            out.push(tt.clone());
+                            continue;
+                        }
+                    }
+
+                    let span = Span::mixed_site();
+
+                    let value = if ident == "break" {
+                        // The value extends to the end of the expression.
+                        let start = n + 1;
+                        let end = tokens[start..]
+                            .iter()
+                            .position(is_terminator)
+                            .map_or(tokens.len(), |len| start + len);
+
+                        for _ in start..end {
+                            let _ = it.next();
+                        }
+
+                        Some(&tokens[start..end])
+                    } else {
+                        None
+                    };
+
+                    let variant = match value {
+                        Some([]) => {
+                            self.is_break = true;
+                            "Break"
+                        }
+                        Some(..) => {
+                            self.is_break_with = true;
+                            "BreakWith"
+                        }
+                        None => {
+                            self.is_continue = true;
+                            "Continue"
+                        }
+                    };
+
+                    let value = value.filter(|value| !value.is_empty()).map(parens);
+
+                    out.write(
+                        span,
+                        braced((
+                            (var, '=', support, "Control", S, variant, value, ';'),
+                            ("break", label(name)),
+                        )),
+                    );
+                }
+                TokenTree::Group(group) => {
+                    let stream = self.rewrite(group.stream(), support, var, name);
+                    let mut new = Group::new(group.delimiter(), stream.into_token_stream());
+                    new.set_span(group.span());
+                    out.push(TokenTree::Group(new));
+                }
+                tt => {
+                    out.push(tt.clone());
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Test if the token starts a label.
+fn is_label(tt: Option<&TokenTree>) -> bool {
+    matches!(tt, Some(TokenTree::Punct(p)) if p.as_char() == '\'')
+}
+
+/// Test if the token terminates the value of a `break` expression.
+fn is_terminator(tt: &TokenTree) -> bool {
+    matches!(tt, TokenTree::Punct(p) if matches!(p.as_char(), ';' | ',') && p.spacing() == Spacing::Alone)
+}
@@ -1,3 +1,5 @@
+mod control;
+
 mod output;
 pub(crate) use self::output::Mode;
 
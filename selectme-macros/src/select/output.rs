@@ -1,15 +1,21 @@
 use core::ops;
 
-use proc_macro::TokenTree;
+use proc_macro::{Span, TokenTree};
 
+use crate::error::Error;
 use crate::into_tokens::{
-    braced, bracketed, from_fn, group, parens, string, IntoTokens, SpannedStream,
+    braced, bracketed, from_fn, group, label, parens, string, IntoTokens, SpannedStream,
 };
-use crate::select::parser::{Block, Branch, Else};
+use crate::select::control::Control;
+use crate::select::parser::{Block, Branch, BranchKind, Cfg, Else, OnTimeout, Panicked, NO_TIMER};
 use crate::tok::{self, S};
+use crate::token_stream::TokenStream;
 
 /// Limit to the number of branches we support.
 pub(crate) const BRANCH_LIMIT: usize = u128::BITS as usize;
+/// Limit to the number of branches in an `inline!` select, which is bounded by
+/// the state tuples that `StateField` is implemented for.
+pub(crate) const INLINE_BRANCH_LIMIT: usize = 16;
 
 /// The name of the output enum.
 const OUT: &str = "Out";
@@ -17,11 +23,25 @@ const OUT: &str = "Out";
 const PRIVATE: &str = "private";
 // Note the lack of convoluted naming. These are not visible in the
 // corresponding branch scopes.
-const MAYBE_FUT: &str = "maybe_fut";
 const FUT: &str = "fut";
 const CX: &str = "cx";
 const STATE: &str = "state";
 const MASK: &str = "mask";
+const INITIALIZED: &str = "initialized";
+const PAYLOAD: &str = "payload";
+const PANICKED: &str = "panicked";
+const PERMIT: &str = "permit";
+const PTR: &str = "ptr";
+const FINISHED: &str = "finished";
+const OUTPUT: &str = "output";
+const POLL: &str = "poll";
+const GUARDS: &str = "guards";
+const OUTPUTS: &str = "outputs";
+const RESULTS: &str = "results";
+const QUORUM: &str = "quorum";
+const CONTROL: &str = "control";
+const TIMER: &str = "timer";
+const TIMEOUT: &str = "timeout";
 
 /// Expansion mode.
 #[derive(Debug, Clone, Copy)]
@@ -30,12 +50,43 @@ pub(crate) enum Mode {
     Inline,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub(crate) enum SelectKind {
+    #[default]
     Select,
     StaticSelect,
 }
 
+/// Options specified at the start of a select.
+#[derive(Default)]
+pub(crate) struct Options {
+    /// If the select is biased.
+    pub(crate) biased: bool,
+    /// The kind of select to generate.
+    pub(crate) select_kind: SelectKind,
+    /// Range for the expression used to construct the timer.
+    pub(crate) timer: Option<ops::Range<usize>>,
+    /// Range for the number of branches which have to complete.
+    pub(crate) quorum: Option<ops::Range<usize>>,
+    /// Range for the future which cancels the select. It's polled by an
+    /// implicit branch, which is always the last branch.
+    pub(crate) cancel: Option<ops::Range<usize>>,
+    /// If the state of the select should be boxed.
+    pub(crate) boxed: bool,
+    /// If branch futures should be asserted to be `Send`.
+    pub(crate) send: bool,
+    /// If branch futures should be asserted to be cancellation safe.
+    pub(crate) cancel_safe: bool,
+    /// If the `compat(futures)` option is used, in which case branches which
+    /// are terminated fused futures are disabled.
+    pub(crate) compat: bool,
+    /// The `default` arm of the `compat(futures)` option, which is evaluated
+    /// if no branch is ready when the select is first polled.
+    pub(crate) default: Option<Block>,
+    /// The `context` ident and the range of its type.
+    pub(crate) context: Option<(proc_macro::Ident, ops::Range<usize>)>,
+}
+
 /// The parsed output.
 pub(crate) struct Output {
     tokens: Vec<TokenTree>,
@@ -43,8 +94,8 @@ pub(crate) struct Output {
     krate: ops::Range<usize>,
     branches: Vec<Branch>,
     else_branch: Option<Else>,
-    biased: bool,
-    select_kind: SelectKind,
+    panicked: Option<Panicked>,
+    options: Options,
 }
 
 impl Output {
@@ -55,8 +106,8 @@ impl Output {
         krate: ops::Range<usize>,
         branches: Vec<Branch>,
         else_branch: Option<Else>,
-        biased: bool,
-        select_kind: SelectKind,
+        panicked: Option<Panicked>,
+        options: Options,
     ) -> Self {
         Self {
             tokens,
@@ -64,8 +115,8 @@ impl Output {
             krate,
             branches,
             else_branch,
-            biased,
-            select_kind,
+            panicked,
+            options,
         }
     }
 
@@ -79,19 +130,62 @@ impl Output {
     fn out_enum(&self) -> impl IntoTokens + '_ {
         (
             ("pub", "enum", OUT),
-            branch_generics(&self.branches),
+            self.branch_generics(),
             braced(from_fn(|s| {
                 for b in &self.branches {
+                    s.write(self.cfg(b));
                     s.write((b.variant.as_ref(), parens(b.generic.as_ref()), ','));
+
+                    if let Some(t) = &b.on_timeout {
+                        s.write((self.cfg(b), t.variant.as_ref(), ','));
+                    }
                 }
 
                 if self.else_branch.is_some() {
                     s.write(("Disabled", ','));
                 }
+
+                if self.panicked.is_some() {
+                    let payload = (self.support(), "Payload");
+                    s.write(("Panicked", parens(("u32", ',', payload)), ','));
+                }
             })),
         )
     }
 
+    /// Generics of the output enumeration.
+    fn branch_generics(&self) -> impl IntoTokens + '_ {
+        from_fn(move |s| {
+            s.write('<');
+
+            for b in &self.branches {
+                s.write((self.cfg(b), b.generic.as_ref(), ','));
+            }
+
+            s.write('>');
+        })
+    }
+
+    /// The `#[cfg(..)]` attribute of a branch, if it has one.
+    fn cfg<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        b.cfg
+            .as_ref()
+            .map(|cfg| ('#', bracketed(("cfg", parens(self.predicate(cfg))))))
+    }
+
+    /// The negated `#[cfg(..)]` attribute of a branch.
+    fn cfg_not<'a>(&'a self, cfg: &'a Cfg) -> impl IntoTokens + 'a {
+        (
+            '#',
+            bracketed(("cfg", parens(("not", parens(self.predicate(cfg)))))),
+        )
+    }
+
+    /// The combined predicate of the `#[cfg(..)]` attributes of a branch.
+    fn predicate<'a>(&'a self, cfg: &'a Cfg) -> impl IntoTokens + 'a {
+        ("all", parens(&self.tokens[cfg.range.clone()]))
+    }
+
     /// Private module declaration.
     fn private_mod(&self) -> impl IntoTokens + '_ {
         let private_mod = braced(self.out_enum());
@@ -99,21 +193,347 @@ impl Output {
     }
 
     fn state(&self) -> impl IntoTokens + '_ {
-        parens(from_fn(|s| {
+        parens(from_fn(move |s| {
             for b in &self.branches {
+                s.write((self.field_state(b), ','));
+            }
+        }))
+    }
+
+    /// Render the boxed state, which is initialized one field at a time. Each
+    /// field passes through a local before being written, so peak stack usage
+    /// is a single field rather than the whole state.
+    fn boxed_state(&self) -> impl IntoTokens + '_ {
+        let ty = parens(from_fn(move |s| {
+            for _ in &self.branches {
+                s.write(("_", ','));
+            }
+        }));
+
+        let field = |index: usize| {
+            (
+                (self.support(), "addr_of_mut", '!'),
+                parens((parens(('*', PTR)), '.', index)),
+            )
+        };
+
+        // Drops the fields which have been initialized if initializing a later
+        // one panics.
+        let drop = from_fn(move |s| {
+            for b in &self.branches {
+                s.write((
+                    ("if", "n", '>', b.index),
+                    braced((self.support(), "drop_in_place", parens(field(b.index)), ';')),
+                ));
+            }
+        });
+
+        let fields = from_fn(move |s| {
+            for b in &self.branches {
+                s.write((
+                    ("let", "value", '=', self.field_state(b), ';'),
+                    (
+                        "unsafe",
+                        braced((
+                            (
+                                self.support(),
+                                "write",
+                                parens((field(b.index), ',', "value")),
+                                ';',
+                            ),
+                            (self.support(), "BoxedInit", S, "initialized"),
+                            (parens(('&', "mut", STATE)), ';'),
+                        )),
+                    ),
+                ));
+            }
+        });
+
+        braced((
+            (
+                ("let", "mut", STATE, '='),
+                (self.support(), "boxed", S, '<', ty, ',', "_", '>'),
+                parens((tok::piped((PTR, ',', "n")), ("unsafe", braced(drop)))),
+                ';',
+            ),
+            (
+                ("let", PTR, '='),
+                (self.support(), "BoxedInit", S, "as_mut_ptr"),
+                (parens(('&', "mut", STATE)), ';'),
+            ),
+            fields,
+            (
+                "unsafe",
+                braced(((self.support(), "BoxedInit", S, "finish"), parens(STATE))),
+            ),
+        ))
+    }
+
+    /// Render the state of a single branch.
+    fn field_state<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            let state = from_fn(move |s| {
                 if let Some(c) = &b.condition {
+                    // NB: `slot` is imported so that lints don't consider
+                    // the expression to be an argument of a call written
+                    // by the user.
                     s.write(tok::if_else(
                         c.var.as_ref(),
-                        tok::option_some(&self.tokens[b.expr.clone()]),
-                        tok::OPTION_NONE,
+                        ("slot", parens(self.branch_state(b))),
+                        ("Slot", S, "empty", parens(())),
                     ));
                 } else {
-                    s.write(&self.tokens[b.expr.clone()]);
+                    s.write(self.branch_state(b));
                 }
+            });
 
-                s.write(',');
+            // Branches which are compiled out keep their position in the
+            // state, but are stored as a unit.
+            if let Some(cfg) = &b.cfg {
+                s.write(braced((
+                    (self.cfg(b), "let", "branch", '=', state, ';'),
+                    (self.cfg_not(cfg), "let", "branch", '=', parens(()), ';'),
+                    "branch",
+                )));
+            } else {
+                s.write(state);
             }
-        }))
+        })
+    }
+
+    /// Render the state of a branch, which is its future together with the
+    /// timeout if it has one.
+    fn branch_state<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| match &b.modifiers.timeout {
+            Some(range) => {
+                let timeout = self.sleep("sleep", &self.tokens[range.clone()]);
+                s.write(parens((self.future(b), ',', timeout)));
+            }
+            None => {
+                s.write(self.future(b));
+            }
+        })
+    }
+
+    /// Render the future expression of a branch.
+    fn future<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            let expr = &self.tokens[b.expr.clone()];
+
+            // Like `futures::select!`, a future which is a local variable is
+            // borrowed so that it can be polled again by a later select.
+            let borrowed = from_fn(move |s| match expr {
+                [TokenTree::Ident(..)] if self.options.compat => {
+                    s.write(('&', "mut", expr));
+                }
+                _ => {
+                    s.write(expr);
+                }
+            });
+
+            let future = from_fn(move |s| match b.kind {
+                // Timer branches are constructed by the timer, so only user
+                // provided futures are checked for cancellation safety.
+                BranchKind::Future if self.options.cancel_safe => {
+                    s.write(("assert_cancel_safe", parens(borrowed)));
+                }
+                BranchKind::Future => s.write(borrowed),
+                BranchKind::After => s.write(self.sleep("sleep", expr)),
+                BranchKind::At => s.write(self.sleep("sleep_until", expr)),
+                BranchKind::Send => {
+                    // The permit is reserved through a method call, so that
+                    // senders with their own `reserve` method like Tokio's are
+                    // used directly instead of through the trait. Those
+                    // futures can't be named to mark them as cancellation
+                    // safe though, so then the trait is used instead.
+                    let reserve = from_fn(move |s| {
+                        if self.options.cancel_safe {
+                            s.write(((self.support(), "SelectSend", S, "reserve"), parens(expr)));
+                        } else {
+                            s.write(braced((
+                                ("use", self.support(), "SelectSend", "as", "_", ';'),
+                                (parens(expr), '.', "reserve", parens(())),
+                            )));
+                        }
+                    });
+
+                    // Handlers of inline selects can't move out of their
+                    // environment, so the value is moved into the branch.
+                    let reserve = from_fn(move |s| match (self.mode, &b.value) {
+                        (Mode::Inline, Some(value)) => {
+                            let value = &self.tokens[value.clone()];
+                            s.write(((self.support(), "sending"), parens((reserve, ',', value))));
+                        }
+                        _ => {
+                            s.write(((self.support(), "reserve"), parens(reserve)));
+                        }
+                    });
+
+                    if self.options.cancel_safe {
+                        s.write(("assert_cancel_safe", parens(reserve)));
+                    } else {
+                        s.write(reserve);
+                    }
+                }
+            });
+
+            // The future is passed straight through the assertion, so that
+            // any error is reported on the branch expression.
+            let future = from_fn(move |s| {
+                if self.options.send {
+                    s.write(("assert_send", parens(future)));
+                } else {
+                    s.write(future);
+                }
+            });
+
+            let delayed = from_fn(move |s| match &b.modifiers.start_after {
+                // The expression is wrapped in a closure so that it's only
+                // evaluated once the branch has started.
+                Some(range) => {
+                    let sleep = self.sleep("sleep", &self.tokens[range.clone()]);
+
+                    let uses_timer = b.kind.uses_timer();
+                    let factory = self.factory(uses_timer, b.modifiers.is_retry(), future);
+                    s.write(((self.support(), "delayed"), parens((sleep, ',', factory))));
+                }
+                None => {
+                    s.write(future);
+                }
+            });
+
+            // Retried branches use the expression as a factory, which is used
+            // to rebuild the future when its pattern doesn't match.
+            let retried = from_fn(move |s| {
+                if !b.modifiers.is_retry() {
+                    s.write(delayed);
+                    return;
+                }
+
+                let attempts = from_fn(move |s| match &b.modifiers.retry {
+                    Some(range) => s.write(tok::option_some(&self.tokens[range.clone()])),
+                    None => s.write(tok::OPTION_NONE),
+                });
+
+                let uses_timer = b.kind.uses_timer() || b.modifiers.start_after.is_some();
+                let factory = self.factory(uses_timer, false, delayed);
+                s.write(((self.support(), "retry"), parens((attempts, ',', factory))));
+            });
+
+            match &b.modifiers.batch {
+                Some(range) => {
+                    let limit = &self.tokens[range.clone()];
+                    s.write(((self.support(), "batch"), parens((limit, ',', retried))));
+                }
+                None => {
+                    s.write(retried);
+                }
+            }
+        })
+    }
+
+    /// Render the context and closure of a branch factory. If the factory
+    /// uses the timer it's passed through the context, since in inline
+    /// selects the factory outlives the timer declared by the select and
+    /// therefore needs its own clone of it. Factories nested inside of
+    /// another factory clone the timer they've been passed.
+    fn factory<'a>(
+        &'a self,
+        uses_timer: bool,
+        nested: bool,
+        body: impl IntoTokens + 'a,
+    ) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            if !uses_timer {
+                s.write((parens(()), ',', tok::piped("_"), body));
+                return;
+            }
+
+            let clone = (self.support(), "Clone", S, "clone");
+
+            match (nested, self.mode) {
+                (true, _) => s.write((clone, parens(TIMER))),
+                (false, Mode::Default) => s.write(('&', TIMER)),
+                (false, Mode::Inline) => s.write((clone, parens(('&', TIMER)))),
+            }
+
+            s.write((',', tok::piped(TIMER), body));
+        })
+    }
+
+    /// Construct a sleep through the timer.
+    fn sleep<'a>(&'a self, method: &'a str, arg: &'a [TokenTree]) -> impl IntoTokens + 'a {
+        (
+            (self.support(), "Timer", S, method),
+            parens(('&', TIMER, ',', arg)),
+        )
+    }
+
+    /// Render the binding of a branch, which for branches without a binding
+    /// matches the unit output. Send branches match the reserved permit, or
+    /// in inline selects that the value has been sent.
+    fn binding<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            if let BranchKind::Send = b.kind {
+                match self.mode {
+                    Mode::Default => s.write(tok::option_some(PERMIT)),
+                    Mode::Inline => s.write(tok::option_some(parens(()))),
+                }
+            } else if b.binding.is_empty() {
+                s.write(parens(()));
+            } else {
+                s.write(&self.tokens[b.binding.clone()]);
+            }
+        })
+    }
+
+    /// Declare the timer used by timer branches, if there are any.
+    fn timer(&self) -> impl IntoTokens + '_ {
+        from_fn(move |s| {
+            let mut users = self.branches.iter().filter(|b| b.timer().is_some());
+
+            let first = match users.next() {
+                Some(b) => b,
+                None => return,
+            };
+
+            // If every branch using the timer is compiled out, so is the
+            // timer.
+            if let Some(cfg) = &first.cfg {
+                if users.clone().all(|b| b.cfg.is_some()) {
+                    let predicates = from_fn(move |s| {
+                        s.write((self.predicate(cfg), ','));
+
+                        for b in users {
+                            if let Some(cfg) = &b.cfg {
+                                s.write((self.predicate(cfg), ','));
+                            }
+                        }
+                    });
+
+                    s.write(('#', bracketed(("cfg", parens(("any", parens(predicates)))))));
+                }
+            }
+
+            s.write(("let", TIMER, '='));
+
+            match &self.options.timer {
+                Some(range) => s.write(&self.tokens[range.clone()]),
+                // NB: The parser only lets a missing default timer through if
+                // every branch which uses it might be compiled out.
+                None if !cfg!(feature = "tokio-timer") => {
+                    let span = first
+                        .timer()
+                        .and_then(|range| self.tokens.get(range.start))
+                        .map(|tt| tt.span());
+
+                    s.write(Error::new(span.unwrap_or_else(Span::call_site), NO_TIMER));
+                }
+                None => s.write((self.support(), "default_timer", parens(()))),
+            }
+
+            s.write(';');
+        })
     }
 
     /// Render the else branch.
@@ -137,34 +557,42 @@ impl Output {
             for b in &self.branches {
                 // We need to allow unreachable cause the expression that
                 // generates the index might not be expressed.
+                s.write(self.cfg(b));
                 s.write(self.allow_unreachable_code());
                 s.write((b.index, tok::ROCKET));
 
-                let fut = (
-                    "unsafe",
-                    braced((
-                        ("Pin", S, "map_unchecked_mut"),
-                        parens((STATE, ',', tok::piped("f"), '&', "mut", "f", '.', b.index)),
-                    )),
+                let field = (
+                    ("Pin", S, "map_unchecked_mut"),
+                    parens((STATE, ',', tok::piped("f"), '&', "mut", "f", '.', b.index)),
                 );
 
-                if b.condition.is_some() {
-                    let assign = ("let", "mut", MAYBE_FUT, '=', fut, ';');
-                    let poll = self.poll_body(b, Some(MAYBE_FUT));
+                // Conditional branches are stored in slots, which are only
+                // enabled if they've been initialized.
+                let fut = from_fn(move |s| {
+                    if b.condition.is_some() {
+                        let project = (self.support(), "project_slot", parens(field));
+                        s.write(("unsafe", braced(project)));
+                    } else {
+                        s.write(("unsafe", braced(field)));
+                    }
+                });
 
-                    let poll = (
-                        ("if", "let", tok::option_some(FUT), '='),
-                        ("Option", S, "as_pin_mut"),
-                        parens(tok::pin_as_mut(MAYBE_FUT)),
-                        braced(poll),
-                    );
+                // Branches with a timeout store their future together with
+                // the timeout, so we project the pair into its parts.
+                let project = b.modifiers.timeout.is_some().then(|| {
+                    (
+                        ("let", parens(("mut", FUT, ',', TIMEOUT)), '='),
+                        (self.support(), "project_timeout", parens(FUT), ';'),
+                    )
+                });
 
-                    s.write(braced((assign, poll)));
-                } else {
-                    let assign = ("let", FUT, '=', fut, ';');
-                    let poll = self.poll_body(b, None);
-                    s.write(braced((assign, poll)));
-                }
+                let assign = ("let", "mut", FUT, '=', fut, ';');
+                s.write(braced((
+                    assign,
+                    project,
+                    self.terminated(b),
+                    self.poll_body(b),
+                )));
             }
 
             if let Some(e) = &self.else_branch {
@@ -187,7 +615,17 @@ impl Output {
     fn match_branch<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
         from_fn(move |s| match self.mode {
             Mode::Default => {
-                let pat = clean_pattern(self.tokens[b.binding.clone()].iter().cloned());
+                let pat = from_fn(|s| {
+                    if let BranchKind::Send = b.kind {
+                        s.write(tok::option_some("_"));
+                    } else if b.binding.is_empty() {
+                        s.write(parens(()));
+                    } else {
+                        s.write(clean_pattern(
+                            self.tokens[b.binding.clone()].iter().cloned(),
+                        ));
+                    }
+                });
 
                 let body = ((PRIVATE, S, OUT, S, b.variant.as_ref()), parens("out"));
 
@@ -205,14 +643,14 @@ impl Output {
                 ));
             }
             Mode::Inline => {
-                let pat = &self.tokens[b.binding.clone()];
+                let pat = self.binding(b);
                 s.write((
                     "if",
                     "let",
                     pat,
                     '=',
                     "out",
-                    braced(("return", tok::poll_ready(self.block(&b.block)), ';')),
+                    braced(("return", tok::poll_ready(self.handler(b)), ';')),
                 ));
             }
         })
@@ -221,9 +659,185 @@ impl Output {
     /// Generate the output matching branch.
     fn out_branch<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
         (
+            self.cfg(b),
             (PRIVATE, S, OUT, S, b.variant.as_ref()),
-            parens(&self.tokens[b.binding.clone()]),
-            (tok::ROCKET, self.block(&b.block)),
+            parens(self.binding(b)),
+            (tok::ROCKET, self.handler(b)),
+        )
+    }
+
+    /// Generate the output matching `timeout` arm of a branch.
+    fn out_timeout<'a>(&'a self, b: &'a Branch, t: &'a OnTimeout) -> impl IntoTokens + 'a {
+        (
+            self.cfg(b),
+            (PRIVATE, S, OUT, S, t.variant.as_ref()),
+            (tok::ROCKET, self.block(&t.block)),
+        )
+    }
+
+    /// Generate the function identifying which branch an output of a select
+    /// with the `quorum` option belongs to. Outputs of `timeout` arms don't
+    /// count as their branch completing.
+    fn quorum_branch(&self) -> impl IntoTokens + '_ {
+        let arms = from_fn(move |s| {
+            for b in &self.branches {
+                s.write((
+                    self.cfg(b),
+                    (PRIVATE, S, OUT, S, b.variant.as_ref()),
+                    parens(['.', '.']),
+                    (tok::ROCKET, tok::option_some(b.index), ','),
+                ));
+            }
+
+            s.write(("_", tok::ROCKET, tok::OPTION_NONE));
+        });
+
+        (tok::piped(OUTPUT), braced(("match", OUTPUT, braced(arms))))
+    }
+
+    /// Collect the outputs of a select with the `quorum` option, where every
+    /// output is tagged with the index of its branch.
+    fn out_quorum(&self) -> impl IntoTokens + '_ {
+        let arms = from_fn(move |s| {
+            for b in &self.branches {
+                s.write((
+                    self.cfg(b),
+                    (PRIVATE, S, OUT, S, b.variant.as_ref()),
+                    parens(self.binding(b)),
+                    (tok::ROCKET, parens((b.index, ',', self.handler(b))), ','),
+                ));
+
+                if let Some(t) = &b.on_timeout {
+                    s.write((
+                        self.cfg(b),
+                        (PRIVATE, S, OUT, S, t.variant.as_ref()),
+                        (
+                            tok::ROCKET,
+                            parens((b.index, ',', self.block(&t.block))),
+                            ',',
+                        ),
+                    ));
+                }
+            }
+
+            let panic_ = (
+                ("unreachable", '!'),
+                parens(string("branch cannot be reached")),
+            );
+
+            s.write(("_", tok::ROCKET, braced(panic_)));
+        });
+
+        let fallback = from_fn(move |s| match &self.else_branch {
+            Some(e) => {
+                s.write(self.block(&e.block));
+            }
+            None => {
+                let panic_ = (
+                    ("panic", '!'),
+                    parens(string("quorum of branches can no longer be reached")),
+                );
+
+                s.write(braced(panic_));
+            }
+        });
+
+        let collect = from_fn(move |s| {
+            // NB: Handlers are evaluated in a loop over the outputs, so
+            // unlabeled `break` and `continue` in them are rewritten to leave
+            // the loop and evaluated again in the enclosing scope afterwards.
+            let mut control = Control::default();
+            let mut handlers = TokenStream::default();
+            handlers.write(Span::mixed_site(), ("match", OUTPUT, braced(arms)));
+            let handlers = control.rewrite(
+                handlers.into_token_stream(),
+                self.support(),
+                CONTROL,
+                QUORUM,
+            );
+
+            s.write((
+                ("let", "mut", RESULTS, ':'),
+                (
+                    self.support(),
+                    "Vec",
+                    '<',
+                    parens(("u32", ',', "_")),
+                    '>',
+                    '=',
+                ),
+                (self.support(), "Vec", S, "with_capacity"),
+                parens((OUTPUTS, '.', "len", parens(()))),
+                ';',
+            ));
+
+            let handle = (
+                '#',
+                bracketed(("allow", parens("unreachable_code"))),
+                (RESULTS, '.', "push", parens(handlers), ';'),
+            );
+
+            if !control.is_used() {
+                s.write(("for", OUTPUT, "in", OUTPUTS, braced(handle), RESULTS));
+                return;
+            }
+
+            // The value of `break <value>` is only known if it's used.
+            let ty = from_fn(|s| {
+                if control.is_break_with {
+                    s.write("_");
+                } else {
+                    s.write(parens(()));
+                }
+            });
+
+            s.write((
+                ("let", "mut", CONTROL, ':'),
+                (self.support(), "Control", '<', ty, '>', '='),
+                (self.support(), "Control", S, "Proceed", ';'),
+            ));
+
+            s.write((
+                (label(QUORUM), ':'),
+                ("for", OUTPUT, "in", OUTPUTS, braced(handle)),
+            ));
+
+            let jump = |variant: &'static str| ("if", "let", self.support(), "Control", S, variant);
+
+            if control.is_break {
+                s.write((jump("Break"), '=', CONTROL, braced(("break", ';'))));
+            }
+
+            if control.is_break_with {
+                s.write((
+                    (jump("BreakWith"), parens("value"), '=', CONTROL),
+                    braced(("break", "value", ';')),
+                ));
+            }
+
+            if control.is_continue {
+                s.write((jump("Continue"), '=', CONTROL, braced(("continue", ';'))));
+            }
+
+            s.write(RESULTS);
+        });
+
+        (
+            (tok::option_some(OUTPUTS), tok::ROCKET, braced(collect), ','),
+            (tok::OPTION_NONE, tok::ROCKET, fallback),
+        )
+    }
+
+    /// Generate the output matching `panicked` arm.
+    fn out_panicked<'a>(&'a self, p: &'a Panicked) -> impl IntoTokens + 'a {
+        (
+            (PRIVATE, S, OUT, S, "Panicked"),
+            parens((
+                &self.tokens[p.index.clone()],
+                ',',
+                &self.tokens[p.payload.clone()],
+            )),
+            (tok::ROCKET, self.block(&p.block)),
         )
     }
 
@@ -235,6 +849,24 @@ impl Output {
         )
     }
 
+    /// Render the handler of a branch. Send branches in `select!` first send
+    /// their value using the permit they've reserved.
+    fn handler<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| match (self.mode, &b.value) {
+            (Mode::Default, Some(value)) => {
+                let send = (
+                    (self.support(), "SendPermit", S, "send"),
+                    parens((PERMIT, ',', &self.tokens[value.clone()])),
+                );
+
+                s.write(braced((send, ';', self.block(&b.block))));
+            }
+            _ => {
+                s.write(self.block(&b.block));
+            }
+        })
+    }
+
     /// Render a parsed block.
     fn block<'a>(&'a self, block: &'a Block) -> impl IntoTokens + 'a {
         from_fn(move |s| match block {
@@ -247,39 +879,184 @@ impl Output {
         })
     }
 
+    /// With the `compat(futures)` option a branch whose future has terminated
+    /// is disabled instead of being polled, like it is by `futures::select!`.
+    fn terminated<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        let is_plain = matches!(b.kind, BranchKind::Future)
+            && !b.modifiers.is_retry()
+            && b.modifiers.start_after.is_none()
+            && b.modifiers.batch.is_none();
+
+        (self.options.compat && is_plain).then(|| {
+            (
+                ("if", self.support(), "is_terminated"),
+                (
+                    parens(('&', '*', FUT)),
+                    braced((MASK, '.', "clear", parens(b.index), ';')),
+                ),
+                "else",
+            )
+        })
+    }
+
     /// Expand the poll expression.
-    fn poll_body<'a>(&'a self, b: &'a Branch, unset: Option<&'a str>) -> impl IntoTokens + 'a {
-        let future_poll = ("Future", S, "poll", parens((FUT, ',', CX)));
+    fn poll_body<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            let branch = (self.support(), "SelectBranch", S);
+            let future_poll = self.future_poll(b, tok::pin_as_mut(FUT));
 
-        (
-            ("if", "let", tok::poll_ready("out"), '='),
-            future_poll,
-            braced((
-                unset.map(|var| (var, '.', "set", parens(tok::OPTION_NONE), ';')),
-                // Unset the current branch in the mask, since it completed.
+            // Branches which aren't finished stay enabled, and branches whose
+            // pattern doesn't match might be re-armed. In both cases the task
+            // is woken up so that the branch is polled again.
+            let rearm = (
+                ("if", '!', FINISHED, ['|', '|']),
+                (branch, "rearm", parens(FUT)),
+                braced((
+                    (MASK, '.', "set", parens(b.index), ';'),
+                    (CX, '.', "waker", parens(()), '.'),
+                    ("wake_by_ref", parens(()), ';'),
+                )),
+            );
+
+            s.write((
+                ("if", "let", tok::poll_ready("out"), '='),
+                future_poll,
+                braced((
+                    (
+                        ("let", FINISHED, '='),
+                        (branch, "is_finished", parens(('&', '*', FUT)), ';'),
+                    ),
+                    // Unset the current branch in the mask, since it completed.
+                    (
+                        ("if", FINISHED),
+                        braced((MASK, '.', "clear", parens(b.index), ';')),
+                    ),
+                    self.match_branch(b),
+                    rearm,
+                )),
+                self.timeout(b),
+            ));
+        })
+    }
+
+    /// Poll the future of a branch. With the `catch_unwind` option a panic
+    /// disables the branch and completes the select through the `panicked`
+    /// arm.
+    fn future_poll<'a, T>(&'a self, b: &'a Branch, fut: T) -> impl IntoTokens + 'a
+    where
+        T: IntoTokens + 'a,
+    {
+        from_fn(move |s| {
+            let p = match &self.panicked {
+                Some(p) => p,
+                None => {
+                    let poll = (self.support(), "SelectBranch", S, "poll_branch");
+                    s.write((poll, parens((fut, ',', CX))));
+                    return;
+                }
+            };
+
+            let output = from_fn(move |s| match self.mode {
+                Mode::Default => {
+                    s.write((
+                        (PRIVATE, S, OUT, S, "Panicked"),
+                        parens((b.index, ',', PAYLOAD)),
+                    ));
+                }
+                Mode::Inline => {
+                    let pat = parens((
+                        &self.tokens[p.index.clone()],
+                        ',',
+                        &self.tokens[p.payload.clone()],
+                    ));
+
+                    s.write((
+                        (
+                            "let",
+                            PANICKED,
+                            ':',
+                            parens(("u32", ',', self.support(), "Payload")),
+                        ),
+                        ('=', parens((b.index, ',', PAYLOAD)), ';'),
+                        (
+                            "match",
+                            PANICKED,
+                            braced((pat, tok::ROCKET, self.block(&p.block))),
+                        ),
+                    ));
+                }
+            });
+
+            let err = braced((
                 (MASK, '.', "clear", parens(b.index), ';'),
-                self.match_branch(b),
-            )),
-        )
+                ("return", tok::poll_ready(braced(output)), ';'),
+            ));
+
+            s.write((
+                (
+                    "match",
+                    self.support(),
+                    "poll_unwind",
+                    parens((fut, ',', CX)),
+                ),
+                braced((
+                    ("Result", S, "Ok", parens("poll"), tok::ROCKET, "poll", ','),
+                    ("Result", S, "Err", parens(PAYLOAD), tok::ROCKET, err),
+                )),
+            ));
+        })
+    }
+
+    /// If the branch has timed out, it is disabled. Without a `timeout` arm
+    /// the select keeps running, otherwise it completes through the arm.
+    fn timeout<'a>(&'a self, b: &'a Branch) -> impl IntoTokens + 'a {
+        let on_timeout = b.on_timeout.as_ref().map(move |t| {
+            from_fn(move |s| match self.mode {
+                Mode::Default => {
+                    let out = (PRIVATE, S, OUT, S, t.variant.as_ref());
+                    s.write(("return", tok::poll_ready(out), ';'));
+                }
+                Mode::Inline => {
+                    s.write(("return", tok::poll_ready(self.block(&t.block)), ';'));
+                }
+            })
+        });
+
+        b.modifiers.timeout.is_some().then(|| {
+            (
+                ("else", "if", "let", tok::poll_ready(parens(())), '='),
+                ("Future", S, "poll", parens((TIMEOUT, ',', CX))),
+                braced(((MASK, '.', "clear", parens(b.index), ';'), on_timeout)),
+            )
+        })
     }
 
     fn conditions(&self, s: &mut SpannedStream<'_>) -> usize {
         let mut reset_base = 0;
 
         for b in &self.branches {
-            match &b.condition {
-                Some(c) => {
+            // Branches which are compiled out are always disabled.
+            let var = match (&b.condition, &b.cfg) {
+                (Some(c), _) => {
                     s.write((
-                        "let",
-                        c.var.as_ref(),
-                        '=',
-                        &self.tokens[c.range.clone()],
-                        ';',
+                        (self.cfg(b), "let", c.var.as_ref(), '='),
+                        (&self.tokens[c.range.clone()], ';'),
                     ));
+
+                    c.var.as_ref()
                 }
-                None => {
+                (None, Some(cfg)) => {
+                    s.write((self.cfg(b), "let", cfg.var.as_ref(), '=', "true", ';'));
+                    cfg.var.as_ref()
+                }
+                (None, None) => {
                     reset_base += 1 << b.index;
+                    continue;
                 }
+            };
+
+            if let Some(cfg) = &b.cfg {
+                s.write((self.cfg_not(cfg), "let", var, '=', "false", ';'));
             }
         }
 
@@ -300,15 +1077,39 @@ impl Output {
     /// Generates the expression that should initially be used as a mask. This
     /// ensures that disabled branches stay disabled even if woken up..
     fn mask_expr(&self, reset_base: usize) -> impl IntoTokens + '_ {
-        let mask_expr = from_fn(move |s| {
-            let mut it = self
-                .branches
-                .iter()
-                .filter_map(|b| b.condition.as_ref().map(|c| (b, c.var.as_ref())));
+        let mask_expr = self.mask_bits(reset_base, |b| match (&b.condition, &b.cfg) {
+            (Some(c), _) => Some(c.var.as_ref()),
+            (None, cfg) => cfg.as_ref().map(|cfg| cfg.var.as_ref()),
+        });
+
+        braced((
+            ("let", MASK, ':', self.mask_type(), '=', mask_expr, ';'),
+            MASK,
+        ))
+    }
+
+    /// Generates the mask of branches which are re-enabled when a static select
+    /// is reset, which are the unconditional branches which are compiled in.
+    fn reset_expr(&self, reset_base: usize) -> impl IntoTokens + '_ {
+        self.mask_bits(reset_base, |b| match (&b.condition, &b.cfg) {
+            (None, Some(cfg)) => Some(cfg.var.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Sum up `base` with the bits of the branches which are enabled by the
+    /// variable returned by `var`.
+    fn mask_bits<'a>(
+        &'a self,
+        base: usize,
+        var: fn(&'a Branch) -> Option<&'a str>,
+    ) -> impl IntoTokens + 'a {
+        from_fn(move |s| {
+            let mut it = self.branches.iter().filter_map(move |b| Some((b, var(b)?)));
 
             if let Some((b, var)) = it.next_back() {
-                if reset_base != 0 {
-                    s.write((reset_base, '+'));
+                if base != 0 {
+                    s.write((base, '+'));
                 }
 
                 for (b, var) in it {
@@ -317,23 +1118,27 @@ impl Output {
 
                 s.write(tok::if_else(var, 1 << b.index, 0));
             } else {
-                s.write(reset_base);
+                s.write(base);
             }
-        });
-
-        braced((
-            ("let", MASK, ':', self.mask_type(), '=', mask_expr, ';'),
-            MASK,
-        ))
+        })
     }
 
-    /// Generate bias.
+    /// Generate bias. The cancellation branch is always polled first.
     fn bias(&self) -> impl IntoTokens + '_ {
-        if self.biased {
-            (self.support(), "unbiased", parens(()))
-        } else {
-            (self.support(), "random", parens(()))
-        }
+        from_fn(move |s| {
+            let bias = if self.options.biased {
+                (self.support(), "unbiased", parens(()))
+            } else {
+                (self.support(), "random", parens(()))
+            };
+
+            if self.options.cancel.is_some() {
+                let index = self.branches.len() - 1;
+                s.write(((self.support(), "prioritized"), parens((bias, ',', index))));
+            } else {
+                s.write(bias);
+            }
+        })
     }
 
     /// Generate imports.
@@ -341,43 +1146,140 @@ impl Output {
         (
             "use",
             self.support(),
-            braced(("Future", ',', "Pin", ',', "Poll")),
+            braced((
+                ("Future", ',', "Pin", ',', "Poll", ','),
+                ("Slot", ',', "slot", ','),
+                ("assert_send", ',', "assert_cancel_safe"),
+            )),
             ';',
         )
     }
 
     /// Setup the poll declaration.
     fn poll_decl(&self, reset_base: usize) -> impl IntoTokens + '_ {
+        let is_static = match (self.mode, self.options.select_kind) {
+            // Default mode doesn't require anything to be captured, since the
+            // branches are evaluated outside of the poller implementation.
+            // While this will probably be optimized out *anyways* we can
+            // instead use a `static_select` ahead of time.
+            (Mode::Default, _) | (_, SelectKind::StaticSelect) => true,
+            _ => false,
+        };
+
         let match_body = ("match", "index", braced(self.matches()));
         let fallback = ("Poll", S, "Pending");
 
+        // Static pollers receive the context passed to `poll_next_with`, which
+        // is bound using the span of the `context` option so that it's
+        // accessible to handlers.
+        let context = is_static.then(|| {
+            from_fn(move |s| {
+                match &self.options.context {
+                    Some((ident, ty)) => {
+                        s.write((TokenTree::Ident(ident.clone()), ':', '&', "mut"));
+                        s.write(&self.tokens[ty.clone()]);
+                    }
+                    None => {
+                        s.write(("_", ':', '&', "mut", parens(())));
+                    }
+                }
+
+                s.write(',');
+            })
+        });
+
+        // Boxed state is projected into the pinned state tuple up front.
+        let project = self.options.boxed.then(|| {
+            (
+                ("let", STATE, '='),
+                (self.support(), "project_boxed", parens(STATE), ';'),
+            )
+        });
+
         let poll_body = (
-            tok::piped((CX, ',', STATE, ',', MASK, ',', "index")),
-            braced((match_body, fallback)),
+            tok::piped((CX, ',', context, STATE, ',', MASK, ',', "index")),
+            braced((project, match_body, fallback)),
         );
 
+        let state = from_fn(move |s| {
+            if self.options.boxed {
+                s.write(self.boxed_state());
+            } else {
+                s.write(self.state());
+            }
+        });
+
         (
             self.support(),
-            match (self.mode, self.select_kind) {
-                // Default mode doesn't require anything to be captured, since
-                // the branches are evaluated outside of the poller
-                // implementation. While this will probably be optimized out
-                // *anyways* we can instead use a `static_select` ahead of time.
-                (Mode::Default, _) | (_, SelectKind::StaticSelect) => "static_select",
-                _ => "select",
-            },
+            if is_static { "static_select" } else { "select" },
             parens((
-                self.mask_expr(reset_base),
-                ',',
-                self.bias(),
-                ',',
-                self.state(),
-                ',',
-                poll_body,
+                (self.mask_expr(reset_base), ','),
+                is_static.then(|| (self.reset_expr(reset_base), ',')),
+                (self.bias(), ',', state, ','),
+                (self.drop_state(), ',', poll_body),
             )),
         )
     }
 
+    /// Generate the function used to drop the state, which is only needed if
+    /// there are conditional branches. Branches are dropped in the order in
+    /// which they are declared.
+    fn drop_state(&self) -> impl IntoTokens + '_ {
+        from_fn(move |s| {
+            if self.branches.iter().all(|b| b.condition.is_none()) {
+                s.write(tok::OPTION_NONE);
+                return;
+            }
+
+            // NB: Fields are dropped through a tuple of guards, so that the
+            // remaining ones are still dropped if dropping one panics.
+            let guards = from_fn(|s| {
+                for b in &self.branches {
+                    let field = ('&', "mut", STATE, '.', b.index);
+
+                    s.write(self.cfg(b));
+
+                    if b.condition.is_some() {
+                        let guard = (self.support(), "DropGuard", S, "slot", parens(field));
+
+                        s.write((
+                            ("if", INITIALIZED, '.', "contains", parens(b.index)),
+                            braced(tok::option_some(guard)),
+                            ("else", braced(tok::OPTION_NONE)),
+                        ));
+                    } else {
+                        s.write((self.support(), "DropGuard", S, "new", parens(field)));
+                    }
+
+                    s.write(',');
+                }
+            });
+
+            let fields = ("let", GUARDS, '=', parens(guards), ';');
+
+            // Boxed state also has to release its allocation.
+            let body = from_fn(move |s| {
+                if self.options.boxed {
+                    s.write((
+                        (self.support(), "drop_boxed"),
+                        parens((STATE, ',', tok::piped(STATE), braced(fields))),
+                    ));
+                } else {
+                    s.write((
+                        ("let", STATE, '='),
+                        ("Pin", S, "get_unchecked_mut", parens(STATE), ';'),
+                        fields,
+                    ));
+                }
+            });
+
+            s.write(tok::option_some((
+                tok::piped((STATE, ',', INITIALIZED)),
+                ("unsafe", braced(body)),
+            )));
+        })
+    }
+
     /// Expand a select which is awaited immediately.
     pub(crate) fn expand(self) -> impl IntoTokens {
         from_fn(move |s| match self.mode {
@@ -385,18 +1287,27 @@ impl Output {
                 s.write(braced(from_fn(move |s| {
                     s.write(self.imports());
                     s.write(self.private_mod());
+                    s.write(self.timer());
 
                     let reset_base = self.conditions(s);
 
                     let output_body = from_fn(|s| {
                         for b in &self.branches {
                             s.write(self.out_branch(b));
+
+                            if let Some(t) = &b.on_timeout {
+                                s.write(self.out_timeout(b, t));
+                            }
                         }
 
                         if let Some(e) = &self.else_branch {
                             s.write(self.out_else(e));
                         }
 
+                        if let Some(p) = &self.panicked {
+                            s.write(self.out_panicked(p));
+                        }
+
                         let panic_ = (
                             ("unreachable", '!'),
                             parens(string("branch cannot be reached")),
@@ -405,18 +1316,79 @@ impl Output {
                         s.write(("_", tok::ROCKET, braced(panic_)));
                     });
 
+                    // NB: The select is awaited in its own statement so that
+                    // the state and with it every branch future is dropped
+                    // before the handler of the winning branch runs.
+                    if let Some(quorum) = &self.options.quorum {
+                        let quorum = &self.tokens[quorum.clone()];
+                        let select = (
+                            (self.support(), "quorum"),
+                            parens((
+                                (quorum, ','),
+                                (self.poll_decl(reset_base), ','),
+                                self.quorum_branch(),
+                            )),
+                        );
+
+                        s.write((("let", OUTPUT, '='), (select, '.', "await", ';')));
+                        s.write(("match", OUTPUT, braced(self.out_quorum())));
+                        return;
+                    }
+
+                    if let Some(default) = &self.options.default {
+                        let select = (
+                            (self.support(), "now_or_never"),
+                            parens(self.poll_decl(reset_base)),
+                        );
+
+                        s.write((("let", OUTPUT, '='), (select, '.', "await", ';')));
+
+                        s.write((
+                            ("match", OUTPUT),
+                            braced((
+                                (tok::option_some(OUTPUT), tok::ROCKET),
+                                ("match", OUTPUT, braced(output_body), ','),
+                                (tok::OPTION_NONE, tok::ROCKET, self.block(default)),
+                            )),
+                        ));
+
+                        return;
+                    }
+
                     s.write((
-                        "match",
-                        (self.poll_decl(reset_base), '.', "await"),
-                        braced(output_body),
+                        ("let", OUTPUT, '='),
+                        (self.poll_decl(reset_base), '.', "await", ';'),
                     ));
+
+                    s.write(("match", OUTPUT, braced(output_body)));
                 })));
             }
             Mode::Inline => {
                 s.write(braced(from_fn(move |s| {
                     s.write(self.imports());
+                    s.write(self.timer());
                     let reset_base = self.conditions(s);
-                    s.write(self.poll_decl(reset_base));
+
+                    if !matches!(self.options.select_kind, SelectKind::StaticSelect) {
+                        s.write(self.poll_decl(reset_base));
+                        return;
+                    }
+
+                    // NB: Coercing the poller into a function pointer reports
+                    // handlers which capture their environment.
+                    let check = (
+                        tok::piped(POLL),
+                        braced((
+                            ("let", "_", ':', self.support(), "PollFn"),
+                            ('<', "_", ',', "_", ',', "_", ',', "_", '>'),
+                            ('=', '*', POLL, ';'),
+                        )),
+                    );
+
+                    s.write((
+                        (self.support(), "check_poller"),
+                        parens((self.poll_decl(reset_base), ',', check)),
+                    ));
                 })));
             }
         })
@@ -452,15 +1424,3 @@ fn clean_pattern(tree: impl Iterator<Item = TokenTree>) -> impl IntoTokens {
         }
     })
 }
-
-fn branch_generics(branches: &[Branch]) -> impl IntoTokens + '_ {
-    from_fn(move |s| {
-        s.write('<');
-
-        for b in branches {
-            s.write((b.generic.as_ref(), ','));
-        }
-
-        s.write('>');
-    })
-}
@@ -1,14 +1,19 @@
 use core::ops;
 
-use proc_macro::{Delimiter, Ident, Span, TokenTree};
+use proc_macro::{Delimiter, Group, Ident, Spacing, Span, TokenTree};
 
 use crate::error::Error;
-use crate::parsing::{BaseParser, Buf, Punct, COMMA, EQ, ROCKET};
-use crate::select::output::{Mode, Output, SelectKind, BRANCH_LIMIT};
+use crate::parsing::{BaseParser, Buf, Punct, COMMA, EQ, ROCKET, SEMI};
+use crate::select::output::{Mode, Options, Output, SelectKind, BRANCH_LIMIT, INLINE_BRANCH_LIMIT};
 
 enum Segment {
-    Branch(Branch),
+    Branch(Box<Branch>),
     Else(Else),
+    Timeout(Ident, Block),
+    Panicked(Ident, Panicked),
+    Cancelled(Ident, Block),
+    Complete(Ident, Block),
+    Default(Ident, Block),
 }
 
 pub(crate) enum Block {
@@ -23,6 +28,10 @@ impl Block {
     }
 }
 
+/// Error raised if a branch uses the default timer, which isn't available.
+pub(crate) const NO_TIMER: &str =
+    "no timer configured, enable the `tokio` feature or specify one using `timer = <expr>;`";
+
 /// A parser for the `select!` macro.
 pub(crate) struct Parser<'a> {
     base: BaseParser<'a>,
@@ -42,23 +51,157 @@ impl<'a> Parser<'a> {
     pub(crate) fn parse(mut self, mode: Mode) -> Result<Output, Vec<Error>> {
         let mut branches = Vec::new();
         let mut else_branch = None;
-        let mut index = 0;
+        let mut panicked = None::<Panicked>;
+        let mut cancelled = None::<Block>;
+        let mut complete_span = None::<Span>;
 
         if let Err(span) = self.parse_until_reserved(COMMA) {
             self.errors.push(Error::new(span, "expected `,`"));
             return Err(self.errors);
         }
 
-        let mut biased = false;
-        let mut select_kind = None::<(Span, SelectKind)>;
+        let krate = 0..self.base.len();
+
+        // NB: Macros defined with `define_select!` pass their default
+        // `cancelled =>` arm as a braced group following the crate, which
+        // can't be confused with an option or a branch.
+        let default_cancelled = match self.base.nth(0) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                let span = group.span();
+                let tt = self.base.bump();
+                let start = self.base.len();
+                self.base.extend(tt);
+                Some((span, Block::Group(start..self.base.len())))
+            }
+            _ => None,
+        };
+
+        let mut options = Options::default();
+        let mut static_span = None::<Span>;
+        let mut timer_span = None::<Span>;
+        let mut quorum_span = None::<Span>;
+        let mut cancel_span = None::<Span>;
+        let mut context_span = None::<Span>;
+        let mut boxed_span = None::<Span>;
+        let mut send_span = None::<Span>;
+        let mut cancel_safe_span = None::<Span>;
+        let mut catch_unwind_span = None::<Span>;
+        let mut compat_span = None::<Span>;
 
         // Parse options.
-        while matches!(self.base.peek2(), Some((TokenTree::Ident(..), TokenTree::Punct(p))) if p.as_char() == ';')
-        {
+        loop {
+            match self.base.peek2() {
+                Some((TokenTree::Ident(..), TokenTree::Punct(p))) if p.as_char() == ';' => {}
+                Some((TokenTree::Ident(ident), TokenTree::Punct(p)))
+                    if p.as_char() == '=' && p.spacing() == Spacing::Alone =>
+                {
+                    let ident = ident.clone();
+
+                    // NB: `<ident> = <expr>` is only an option if it's
+                    // terminated by a `;`, otherwise it's a branch binding.
+                    if self.base.scan_punct(2, &[SEMI, ROCKET]) != Some(SEMI) {
+                        break;
+                    }
+
+                    let (span, expected, value) = match self.base.buf.display_as_str(&ident) {
+                        "timer" => (
+                            &mut timer_span,
+                            "expected expression following `timer =`",
+                            &mut options.timer,
+                        ),
+                        "quorum" => (
+                            &mut quorum_span,
+                            "expected expression following `quorum =`",
+                            &mut options.quorum,
+                        ),
+                        "cancel" => (
+                            &mut cancel_span,
+                            "expected expression following `cancel =`",
+                            &mut options.cancel,
+                        ),
+                        _ => break,
+                    };
+
+                    self.base.step(2);
+
+                    if let Some(previous) = span.replace(ident.span()) {
+                        self.duplicate_option(&ident, previous);
+                    }
+
+                    *value = self.parse_option_value(&ident, expected);
+                    continue;
+                }
+                Some((TokenTree::Ident(ident), TokenTree::Group(group)))
+                    if group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    let (ident, group) = (ident.clone(), group.clone());
+
+                    if self.base.buf.display_as_str(&ident) != "compat" {
+                        break;
+                    }
+
+                    self.base.step(2);
+
+                    if let Some(previous) = compat_span.replace(ident.span()) {
+                        self.duplicate_option(&ident, previous);
+                    } else if !cfg!(feature = "futures") {
+                        self.errors.push(Error::new(
+                            ident.span(),
+                            requires_feature("`compat` option", "futures"),
+                        ));
+                    } else if !matches!(mode, Mode::Default) {
+                        self.errors.push(Error::new(
+                            ident.span(),
+                            "`compat` option is only supported with the `select!` macro",
+                        ));
+                    }
+
+                    options.compat = true;
+                    let mut stream = group.stream().into_iter();
+
+                    match (stream.next(), stream.next()) {
+                        (Some(TokenTree::Ident(mode)), None)
+                            if self.base.buf.display_as_str(&mode) == "futures" => {}
+                        _ => {
+                            self.errors
+                                .push(Error::new(group.span(), "expected `compat(futures)`"));
+                        }
+                    }
+
+                    if !self.base.skip_punct(SEMI) {
+                        self.errors.push(Error::new(group.span(), "expected `;`"));
+                        self.recover_to_group();
+                    }
+
+                    continue;
+                }
+                Some((TokenTree::Ident(ident), TokenTree::Punct(p)))
+                    if p.as_char() == ':' && p.spacing() == Spacing::Alone =>
+                {
+                    let ident = ident.clone();
+
+                    if self.base.buf.display_as_str(&ident) != "context" {
+                        break;
+                    }
+
+                    self.base.step(2);
+
+                    if let Some(previous) = context_span.replace(ident.span()) {
+                        self.duplicate_option(&ident, previous);
+                    }
+
+                    options.context = self
+                        .parse_option_value(&ident, "expected type following `context:`")
+                        .map(|ty| (ident, ty));
+                    continue;
+                }
+                _ => break,
+            }
+
             match self.base.bump() {
                 Some(TokenTree::Ident(ident)) => match self.base.buf.display_as_str(&ident) {
                     "biased" => {
-                        biased = true;
+                        options.biased = true;
                     }
                     "static" => {
                         if !matches!(mode, Mode::Inline) {
@@ -66,18 +209,49 @@ impl<'a> Parser<'a> {
                                 ident.span(),
                                 "`static` option is only supported with the `inline!` macro",
                             ));
-                        } else if let Some((span, _)) = &select_kind {
-                            self.errors.push(Error::new(
-                                ident.span(),
-                                "`static` option may only be specified once",
-                            ));
-
-                            self.errors.push(Error::new(
-                                *span,
-                                "`static` option previously specified here",
-                            ));
+                        } else if let Some(previous) = static_span {
+                            self.duplicate_option(&ident, previous);
+                        } else {
+                            static_span = Some(ident.span());
+                            options.select_kind = SelectKind::StaticSelect;
+                        }
+                    }
+                    "boxed" => {
+                        if let Some(previous) = boxed_span {
+                            self.duplicate_option(&ident, previous);
+                        } else {
+                            boxed_span = Some(ident.span());
+                            options.boxed = true;
+                        }
+                    }
+                    "send" => {
+                        if let Some(previous) = send_span {
+                            self.duplicate_option(&ident, previous);
+                        } else {
+                            send_span = Some(ident.span());
+                            options.send = true;
+                        }
+                    }
+                    "cancel_safe" => {
+                        if let Some(previous) = cancel_safe_span {
+                            self.duplicate_option(&ident, previous);
+                        } else {
+                            cancel_safe_span = Some(ident.span());
+                            options.cancel_safe = true;
+                        }
+                    }
+                    "catch_unwind" => {
+                        if let Some(previous) = catch_unwind_span {
+                            self.duplicate_option(&ident, previous);
                         } else {
-                            select_kind = Some((ident.span(), SelectKind::StaticSelect));
+                            if !cfg!(feature = "std") {
+                                self.errors.push(Error::new(
+                                    ident.span(),
+                                    requires_feature("`catch_unwind` option", "std"),
+                                ));
+                            }
+
+                            catch_unwind_span = Some(ident.span());
                         }
                     }
                     other => {
@@ -97,35 +271,257 @@ impl<'a> Parser<'a> {
             let _ = self.base.bump();
         }
 
-        let krate = 0..self.base.len();
+        if let (Some(span), None) = (context_span, static_span) {
+            self.errors.push(Error::new(
+                span,
+                "`context` option is only supported together with the `static` option",
+            ));
+        }
+
+        // NB: Without the `alloc` feature the support functions these options
+        // use don't exist, which would otherwise be reported as missing items.
+        if !cfg!(feature = "alloc") {
+            if let Some(span) = boxed_span {
+                self.errors.push(Error::new(
+                    span,
+                    requires_feature("`boxed` option", "alloc"),
+                ));
+            }
+        }
+
+        if let Some(span) = quorum_span {
+            if !cfg!(feature = "alloc") {
+                self.errors.push(Error::new(
+                    span,
+                    requires_feature("`quorum` option", "alloc"),
+                ));
+            } else if !matches!(mode, Mode::Default) {
+                self.errors.push(Error::new(
+                    span,
+                    "`quorum` option is only supported with the `select!` macro",
+                ));
+            } else if catch_unwind_span.is_some() {
+                self.errors.push(Error::new(
+                    span,
+                    "`quorum` option can't be used together with the `catch_unwind` option",
+                ));
+            } else if cancel_span.is_some() {
+                self.errors.push(Error::new(
+                    span,
+                    "`quorum` option can't be used together with the `cancel` option",
+                ));
+            }
+        }
+
+        // Indicates if the previous segment was a branch, in which case it can
+        // be followed by a `timeout =>` arm.
+        let mut previous_branch = false;
 
         while self.base.nth(0).is_some() {
             let mut is_expr = false;
+            let mut is_branch = false;
 
-            if let Some(segment) = self.parse_segment(index) {
+            if let Some(segment) = self.parse_segment(branches.len()) {
                 match segment {
                     Segment::Branch(b) => {
                         is_expr = b.block.is_expr();
-                        branches.push(b);
+                        is_branch = true;
+                        branches.push(*b);
                     }
                     Segment::Else(e) => {
                         is_expr = e.block.is_expr();
+
+                        if let Some(span) = complete_span {
+                            self.errors.push(Error::new(
+                                span,
+                                "`complete` arm can't be used together with an `else` arm",
+                            ));
+                        }
+
                         else_branch = Some(e);
                     }
+                    Segment::Complete(ident, block) => {
+                        is_expr = block.is_expr();
+
+                        if compat_span.is_none() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`complete` arm is only supported together with the `compat(futures)` option",
+                            ));
+                        } else if complete_span.is_some() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`complete` arm may only be specified once",
+                            ));
+                        } else if else_branch.is_some() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`complete` arm can't be used together with an `else` arm",
+                            ));
+                        } else {
+                            complete_span = Some(ident.span());
+                            else_branch = Some(Else { block });
+                        }
+                    }
+                    Segment::Default(ident, block) => {
+                        is_expr = block.is_expr();
+
+                        if compat_span.is_none() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`default` arm is only supported together with the `compat(futures)` option",
+                            ));
+                        } else if options.default.is_some() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`default` arm may only be specified once",
+                            ));
+                        } else if let Some(span) = quorum_span {
+                            self.errors.push(Error::new(
+                                span,
+                                "`quorum` option can't be used together with a `default` arm",
+                            ));
+                        } else {
+                            options.default = Some(block);
+                        }
+                    }
+                    Segment::Panicked(ident, p) => {
+                        is_expr = p.block.is_expr();
+
+                        if catch_unwind_span.is_none() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`panicked` arm is only supported together with the `catch_unwind` option",
+                            ));
+                        } else if panicked.is_some() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`panicked` arm may only be specified once",
+                            ));
+                        } else {
+                            panicked = Some(p);
+                        }
+                    }
+                    Segment::Cancelled(ident, block) => {
+                        is_expr = block.is_expr();
+
+                        if cancel_span.is_none() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`cancelled` arm is only supported together with the `cancel` option",
+                            ));
+                        } else if cancelled.is_some() {
+                            self.errors.push(Error::new(
+                                ident.span(),
+                                "`cancelled` arm may only be specified once",
+                            ));
+                        } else {
+                            cancelled = Some(block);
+                        }
+                    }
+                    Segment::Timeout(ident, block) => {
+                        is_expr = block.is_expr();
+
+                        match branches.last_mut() {
+                            Some(b)
+                                if previous_branch
+                                    && b.modifiers.timeout.is_some()
+                                    && b.on_timeout.is_none() =>
+                            {
+                                b.on_timeout = Some(OnTimeout {
+                                    variant: format!("Timeout{}", b.index).into(),
+                                    block,
+                                });
+                            }
+                            _ => {
+                                self.errors.push(Error::new(
+                                    ident.span(),
+                                    "`timeout` arm must directly follow a branch with a `timeout(..)` modifier",
+                                ));
+                            }
+                        }
+                    }
                 }
             }
 
+            previous_branch = is_branch;
+
             if !self.base.skip_punct(COMMA) && is_expr {
                 break;
             }
-
-            index += 1;
         }
 
         if let Some(tt) = self.base.nth(0) {
             self.errors.push(Error::new(tt.span(), "trailing token"));
         }
 
+        // NB: A malformed `panicked` arm has already been reported.
+        match catch_unwind_span {
+            Some(span) if panicked.is_none() && self.errors.is_empty() => {
+                self.errors.push(Error::new(
+                    span,
+                    "`catch_unwind` option requires a `panicked(<index>, <payload>) =>` arm",
+                ));
+            }
+            _ => {}
+        }
+
+        // A default `cancelled =>` arm is only used if the select doesn't
+        // have one of its own.
+        let cancelled = match (cancelled, default_cancelled) {
+            (Some(block), _) => Some(block),
+            (None, Some((_, block))) if cancel_span.is_some() => Some(block),
+            (None, Some((span, _))) => {
+                self.errors.push(Error::new(
+                    span,
+                    "`cancelled` arm is only supported together with the `cancel` option",
+                ));
+                None
+            }
+            (None, None) => None,
+        };
+
+        match (cancel_span, cancelled) {
+            (Some(span), None) if self.errors.is_empty() => {
+                self.errors.push(Error::new(
+                    span,
+                    "`cancel` option requires a `cancelled =>` arm",
+                ));
+            }
+            (Some(span), Some(block)) => {
+                if let Some(expr) = options.cancel.clone() {
+                    branches.push(self.cancel_branch(span, branches.len(), expr, block));
+                }
+            }
+            _ => {}
+        }
+
+        let uses_timer = branches.iter().any(|b| b.timer().is_some());
+
+        match timer_span {
+            Some(span) if !uses_timer => {
+                self.errors
+                    .push(Error::new(span, "`timer` option is not used by any branch"));
+            }
+            // Without the `tokio` feature there's no default timer. Branches
+            // which might be compiled out are instead reported when the
+            // timer is constructed.
+            None if !cfg!(feature = "tokio-timer") => {
+                let range = branches
+                    .iter()
+                    .filter(|b| b.cfg.is_none())
+                    .find_map(|b| b.timer());
+
+                if let Some(range) = range {
+                    let span = self.base.get(range.start).map(|tt| tt.span());
+
+                    self.errors
+                        .push(Error::new(span.unwrap_or_else(Span::call_site), NO_TIMER));
+                }
+            }
+            _ => {}
+        }
+
         if !self.errors.is_empty() {
             return Err(self.errors);
         }
@@ -146,25 +542,83 @@ impl<'a> Parser<'a> {
             return Err(self.errors);
         }
 
+        // The state of an `inline!` select can be accessed by branch index,
+        // which is only supported for a limited number of branches.
+        if matches!(mode, Mode::Inline) && branches.len() > INLINE_BRANCH_LIMIT {
+            let b = &branches[INLINE_BRANCH_LIMIT];
+            let span = self.base.get(b.expr.start).map(|tt| tt.span());
+
+            self.errors.push(Error::new(
+                span.unwrap_or_else(Span::call_site),
+                format!(
+                    "`inline!` only supports up to {INLINE_BRANCH_LIMIT} branches, you specified {}",
+                    branches.len()
+                ),
+            ));
+            return Err(self.errors);
+        }
+
         Ok(Output::new(
             self.base.into_tokens(),
             mode,
             krate,
             branches,
             else_branch,
-            biased,
-            select_kind
-                .map(|(_, kind)| kind)
-                .unwrap_or(SelectKind::Select),
+            panicked,
+            options,
         ))
     }
 
+    /// Report an option which has been specified more than once.
+    fn duplicate_option(&mut self, ident: &Ident, previous: Span) {
+        let name = self.base.buf.display_as_str(ident);
+
+        let error = Error::new(
+            ident.span(),
+            format!("`{name}` option may only be specified once"),
+        );
+
+        let previous = Error::new(
+            previous,
+            format!("`{name}` option previously specified here"),
+        );
+
+        self.errors.push(error);
+        self.errors.push(previous);
+    }
+
+    /// Parse the value of an option like `<ident> = <expr>;` or
+    /// `<ident>: <type>;`, where the leading `<ident>` and separator have
+    /// already been consumed.
+    fn parse_option_value(&mut self, ident: &Ident, expected: &str) -> Option<ops::Range<usize>> {
+        let start = self.base.len();
+        self.parse_until_eof(SEMI);
+        let value = start..self.base.len();
+
+        if !self.base.skip_punct(SEMI) {
+            let span = self
+                .base
+                .last()
+                .map(|tt| tt.span())
+                .unwrap_or_else(|| ident.span());
+            self.errors.push(Error::new(span, "expected `;`"));
+            return None;
+        }
+
+        if value.is_empty() {
+            self.errors.push(Error::new(ident.span(), expected));
+            return None;
+        }
+
+        Some(value)
+    }
+
     /// Parse a condition up until the `=>` token. Implements basic error
     /// recovery by winding to a group (or a comma).
     fn parse_condition(&mut self, ident: Ident) -> Option<(usize, usize)> {
         let start = self.base.len();
 
-        if let Err(span) = self.parse_until_reserved(ROCKET) {
+        if let Err(span) = self.parse_expr_until_reserved(ROCKET)? {
             self.errors.push(Error::new(span, "expected `=>`"));
             self.recover_to_group();
             return None;
@@ -182,86 +636,397 @@ impl<'a> Parser<'a> {
         None
     }
 
+    /// Parse a branch expression followed by its modifiers and condition.
+    ///
+    /// If `parsed` is set, the expression has already been parsed like the
+    /// argument of a timer branch, so a leading `if` starts the condition
+    /// rather than an `if` expression.
     fn parse_expr(
         &mut self,
         binding: usize,
+        parsed: bool,
+        modifiers: &mut Modifiers,
     ) -> Option<(ops::Range<usize>, Option<ops::Range<usize>>)> {
         let start = self.base.len();
+        let mut generics = Generics::default();
 
         loop {
             match self.base.peek_punct() {
+                _ if generics.is_open() => {}
                 Some(p @ Punct { chars: ROCKET, .. }) => {
                     self.base.step(p.len());
                     return Some((start..self.base.len(), None));
                 }
                 Some(p @ Punct { chars: COMMA, .. }) => {
                     self.base.step(p.len());
+                    let expr = start..self.base.len();
+                    let condition = self.parse_modifiers(modifiers)?;
+                    return Some((expr, condition));
+                }
+                _ => {}
+            }
+
+            match self.base.bump() {
+                Some(TokenTree::Ident(ident))
+                    if self.base.buf.display_as_str(&ident) == "if"
+                        && (parsed || !self.is_expr_start(start)) =>
+                {
+                    let (expr, len) = self.parse_condition(ident)?;
+                    return Some((start..expr, Some(expr..len)));
+                }
+                Some(tt) => {
+                    self.push_expr(start, &mut generics, tt)?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let span = if let Some(tt) = self.base.get(binding..).and_then(|tree| tree.last()) {
+                tt.span()
+            } else {
+                Span::call_site()
+            };
+
+            self.errors.push(Error::new(
+                span,
+                "expected branch expression followed by `=>`",
+            ));
+            return None;
+        }
+    }
+
+    /// Parse a sequence of comma-separated branch modifiers, optionally
+    /// followed by a condition. The leading `,` has already been consumed.
+    ///
+    /// This returns the range of the condition, if present.
+    fn parse_modifiers(&mut self, modifiers: &mut Modifiers) -> Option<Option<ops::Range<usize>>> {
+        loop {
+            let ident = match self.base.bump() {
+                Some(TokenTree::Ident(ident)) => ident,
+                tt => {
+                    let span = tt.map(|tt| tt.span()).unwrap_or_else(Span::call_site);
+
+                    self.errors.push(Error::new(
+                        span,
+                        "expected `if` followed by branch condition",
+                    ));
+                    self.recover_to_group();
+                    return None;
+                }
+            };
+
+            if self.base.buf.display_as_str(&ident) == "if" {
+                let (start, end) = self.parse_condition(ident)?;
+                return Some(Some(start..end));
+            }
+
+            self.parse_modifier(ident, modifiers)?;
+
+            match self.base.peek_punct() {
+                Some(p @ Punct { chars: ROCKET, .. }) => {
+                    self.base.step(p.len());
+                    return Some(None);
+                }
+                Some(p @ Punct { chars: COMMA, .. }) => {
+                    self.base.step(p.len());
+                    continue;
+                }
+                _ => {}
+            }
+
+            match self.base.bump() {
+                Some(TokenTree::Ident(ident)) if self.base.buf.display_as_str(&ident) == "if" => {
+                    let (start, end) = self.parse_condition(ident)?;
+                    return Some(Some(start..end));
+                }
+                tt => {
+                    let span = tt.map(|tt| tt.span()).unwrap_or_else(Span::call_site);
+                    self.errors.push(Error::new(span, "expected `=>`"));
+                    self.recover_to_group();
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Parse a single branch modifier like `timeout(<duration>)`.
+    fn parse_modifier(&mut self, ident: Ident, modifiers: &mut Modifiers) -> Option<()> {
+        let is_group = matches!(
+            self.base.nth(0),
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis
+        );
+
+        // The `repeat` modifier doesn't take any arguments.
+        if !is_group && self.base.buf.display_as_str(&ident) == "repeat" {
+            if modifiers.repeat.replace(ident.span()).is_some() {
+                self.errors.push(Error::new(
+                    ident.span(),
+                    "`repeat` modifier may only be specified once",
+                ));
+                return None;
+            }
+
+            return Some(());
+        }
+
+        let group = match self.base.nth(0) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                group.clone()
+            }
+            _ => {
+                self.errors.push(Error::new(
+                    ident.span(),
+                    "expected `if` followed by branch condition",
+                ));
+                self.recover_to_group();
+                return None;
+            }
+        };
+
+        let slot = match self.base.buf.display_as_str(&ident) {
+            "timeout" => &mut modifiers.timeout,
+            "start_after" => &mut modifiers.start_after,
+            "retry" => &mut modifiers.retry,
+            "batch" => &mut modifiers.batch,
+            other => {
+                let error = Error::new(
+                    ident.span(),
+                    format!("unsupported branch modifier `{other}`"),
+                );
+                self.errors.push(error);
+                self.recover_to_group();
+                return None;
+            }
+        };
+
+        let _ = self.base.bump();
+
+        // NB: The batch limit is checked here, since a limit of zero would
+        // otherwise only be caught at runtime.
+        if self.base.buf.display_as_str(&ident) == "batch" && !cfg!(feature = "alloc") {
+            self.errors.push(Error::new(
+                ident.span(),
+                requires_feature("`batch` modifier", "alloc"),
+            ));
+        } else if self.base.buf.display_as_str(&ident) == "batch" && is_zero(&group) {
+            self.errors.push(Error::new(
+                group.span(),
+                "`batch` limit must be greater than zero",
+            ));
+        }
+
+        let start = self.base.len();
+        self.base.extend(group.stream());
+        let range = start..self.base.len();
+
+        if range.is_empty() {
+            self.errors.push(Error::new(
+                group.span(),
+                format!("expected expression in `{ident}` modifier"),
+            ));
+            return None;
+        }
+
+        if slot.replace(range).is_some() {
+            self.errors.push(Error::new(
+                ident.span(),
+                format!("`{ident}` modifier may only be specified once"),
+            ));
+            return None;
+        }
+
+        Some(())
+    }
+
+    /// Unwind until we find a group and return a boolean indicating if the group was found.
+    fn recover_to_group(&mut self) {
+        while !matches!(self.base.nth(0), Some(TokenTree::Group(..)) | None) {
+            let _ = self.base.bump();
+        }
+
+        let _ = self.base.bump();
+    }
+
+    /// Unwind past the handler of the current branch.
+    fn recover_to_handler(&mut self) {
+        self.parse_until_eof(ROCKET);
+
+        if self.base.skip_punct(ROCKET) {
+            let _ = self.parse_block();
+        }
+    }
+
+    /// Try to parse the `else` keyword and indicate if it was successful.
+    fn try_parse_else(&mut self) -> bool {
+        match self.base.nth(0) {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "else" => {
+                let _ = self.base.bump();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Construct the implicit branch added by the `cancel = <future>;` option,
+    /// which is always the last branch and evaluates the `cancelled` arm.
+    fn cancel_branch(
+        &mut self,
+        span: Span,
+        index: usize,
+        expr: ops::Range<usize>,
+        block: Block,
+    ) -> Branch {
+        let start = self.base.len();
+        self.base.push(TokenTree::Ident(Ident::new("_", span)));
+        let binding = start..self.base.len();
+
+        Branch {
+            index,
+            kind: BranchKind::Future,
+            binding,
+            expr,
+            value: None,
+            block,
+            generic: format!("T{index}").into(),
+            variant: format!("Branch{index}").into(),
+            condition: None,
+            cfg: None,
+            modifiers: Modifiers::default(),
+            on_timeout: None,
+        }
+    }
+
+    /// Try to parse an arm consisting of the given keyword followed by `=>`,
+    /// like the `timeout =>` arm which can follow a branch with a
+    /// `timeout(<duration>)` modifier or the `cancelled =>` arm.
+    fn try_parse_arm(&mut self, keyword: &str) -> Option<Ident> {
+        let ident = match self.base.peek2() {
+            Some((TokenTree::Ident(ident), TokenTree::Punct(p)))
+                if p.as_char() == '=' && p.spacing() == Spacing::Joint =>
+            {
+                ident.clone()
+            }
+            _ => return None,
+        };
+
+        if self.base.buf.display_as_str(&ident) != keyword {
+            return None;
+        }
+
+        let _ = self.base.bump();
+
+        if !self.base.skip_punct(ROCKET) {
+            self.base.push(TokenTree::Ident(ident));
+            return None;
+        }
+
+        Some(ident)
+    }
+
+    /// Try to parse the `panicked(<index>, <payload>) =>` arm.
+    ///
+    /// If the tokens turn out to be the start of a pattern instead, like in
+    /// `panicked(value) = <expr>`, they are retained as part of the binding.
+    fn try_parse_panicked(&mut self) -> Option<(Ident, Group)> {
+        let (ident, group) = match self.base.peek2() {
+            Some((TokenTree::Ident(ident), TokenTree::Group(group)))
+                if group.delimiter() == Delimiter::Parenthesis =>
+            {
+                (ident.clone(), group.clone())
+            }
+            _ => return None,
+        };
+
+        if self.base.buf.display_as_str(&ident) != "panicked" {
+            return None;
+        }
+
+        self.base.step(2);
+
+        if !self.base.skip_punct(ROCKET) {
+            self.base.push(TokenTree::Ident(ident));
+            self.base.push(TokenTree::Group(group));
+            return None;
+        }
+
+        Some((ident, group))
+    }
+
+    /// Parse the `<index>, <payload>` patterns of a `panicked` arm.
+    fn parse_panicked(&mut self, group: &Group) -> Option<(ops::Range<usize>, ops::Range<usize>)> {
+        let pair = self.parse_pair(group);
+
+        if pair.is_none() {
+            self.errors.push(Error::new(
+                group.span(),
+                "expected `panicked(<index>, <payload>)`",
+            ));
+        }
 
-                    let span = match self.base.bump() {
-                        Some(TokenTree::Ident(ident))
-                            if self.base.buf.display_as_str(&ident) == "if" =>
-                        {
-                            let (expr, len) = self.parse_condition(ident)?;
-                            return Some((start..expr, Some(expr..len)));
-                        }
-                        Some(tt) => tt.span(),
-                        None => Span::call_site(),
-                    };
+        pair
+    }
 
-                    self.errors.push(Error::new(
-                        span,
-                        "expected `if` followed by branch condition",
-                    ));
-                    self.recover_to_group();
-                    return None;
-                }
-                _ => {}
-            }
+    /// Parse the comma-separated contents of a group, which is expected to
+    /// contain exactly two non-empty elements.
+    fn parse_pair(&mut self, group: &Group) -> Option<(ops::Range<usize>, ops::Range<usize>)> {
+        let mut ranges = Vec::new();
+        let mut start = self.base.len();
+        let mut generics = Generics::default();
 
-            match self.base.bump() {
-                Some(TokenTree::Ident(ident)) if self.base.buf.display_as_str(&ident) == "if" => {
-                    let (expr, len) = self.parse_condition(ident)?;
-                    return Some((start..expr, Some(expr..len)));
+        for tt in group.stream() {
+            match tt {
+                TokenTree::Punct(p) if p.as_char() == ',' && !generics.is_open() => {
+                    ranges.push(start..self.base.len());
+                    start = self.base.len();
                 }
-                Some(tt) => {
+                tt => {
+                    generics.update(self.base.get(start..).unwrap_or_default(), &tt);
                     self.base.push(tt);
-                    continue;
                 }
-                _ => {}
             }
-
-            let span = if let Some(tt) = self.base.get(binding..).and_then(|tree| tree.last()) {
-                tt.span()
-            } else {
-                Span::call_site()
-            };
-
-            self.errors.push(Error::new(
-                span,
-                "expected branch expression followed by `=>`",
-            ));
-            return None;
         }
-    }
 
-    /// Unwind until we find a group and return a boolean indicating if the group was found.
-    fn recover_to_group(&mut self) {
-        while !matches!(self.base.nth(0), Some(TokenTree::Group(..)) | None) {
-            let _ = self.base.bump();
+        if start != self.base.len() {
+            ranges.push(start..self.base.len());
         }
 
-        let _ = self.base.bump();
+        match <[_; 2]>::try_from(ranges) {
+            Ok([a, b]) if !a.is_empty() && !b.is_empty() => Some((a, b)),
+            _ => None,
+        }
     }
 
-    /// Try to parse the `else` keyword and indicate if it was successful.
-    fn try_parse_else(&mut self) -> bool {
-        match self.base.nth(0) {
-            Some(TokenTree::Ident(ident)) if ident.to_string() == "else" => {
-                let _ = self.base.bump();
-                true
+    /// Try to parse a timer branch like `after(<duration>)` or
+    /// `at(<deadline>)`, or a send branch like `send(<sender>, <value>)`.
+    ///
+    /// If the tokens turn out to be the start of a pattern instead, like in
+    /// `after(value) = <expr>`, they are retained as part of the binding.
+    fn try_parse_timer(&mut self) -> Option<(BranchKind, Group)> {
+        let (ident, group) = match self.base.peek2() {
+            Some((TokenTree::Ident(ident), TokenTree::Group(group)))
+                if group.delimiter() == Delimiter::Parenthesis =>
+            {
+                (ident.clone(), group.clone())
             }
-            _ => false,
+            _ => return None,
+        };
+
+        let kind = match self.base.buf.display_as_str(&ident) {
+            "after" => BranchKind::After,
+            "at" => BranchKind::At,
+            "send" => BranchKind::Send,
+            _ => return None,
+        };
+
+        self.base.step(2);
+
+        if matches!(self.base.peek_punct(), Some(Punct { chars: EQ, .. })) {
+            self.base.push(TokenTree::Ident(ident));
+            self.base.push(TokenTree::Group(group));
+            return None;
         }
+
+        Some((kind, group))
     }
 
     /// Test if the given punctuation is reserved.
@@ -297,8 +1062,12 @@ impl<'a> Parser<'a> {
     /// Parse until we've found an '=' or another punctuation which causes us to
     /// error.
     fn parse_until_reserved(&mut self, expected: [char; 2]) -> Result<(), Span> {
+        let start = self.base.len();
+        let mut generics = Generics::default();
+
         loop {
             match self.base.peek_punct() {
+                _ if generics.is_open() => {}
                 Some(p) if p.chars == expected => {
                     self.base.step(p.len());
                     return Ok(());
@@ -319,6 +1088,7 @@ impl<'a> Parser<'a> {
                     return Err(tt.span());
                 }
 
+                generics.update(self.base.get(start..).unwrap_or_default(), &tt);
                 self.base.push(tt);
                 continue;
             }
@@ -331,6 +1101,151 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse an expression until we've found the expected punctuation, or
+    /// another punctuation or identifier which causes us to error.
+    ///
+    /// Unlike [`Parser::parse_until_reserved`], this accepts `if` expressions.
+    /// Errors in them have already been reported if this returns [`None`].
+    fn parse_expr_until_reserved(&mut self, expected: [char; 2]) -> Option<Result<(), Span>> {
+        let start = self.base.len();
+        let mut generics = Generics::default();
+
+        loop {
+            match self.base.peek_punct() {
+                _ if generics.is_open() => {}
+                Some(p) if p.chars == expected => {
+                    self.base.step(p.len());
+                    return Some(Ok(()));
+                }
+                Some(p) if self.is_reserved_punct(&p) => {
+                    self.base.step(p.len());
+                    return Some(Err(p.span));
+                }
+                _ => {}
+            }
+
+            let tt = match self.base.bump() {
+                Some(tt) => tt,
+                None => {
+                    let span = self.base.last().map(|tt| tt.span());
+                    return Some(Err(span.unwrap_or_else(Span::call_site)));
+                }
+            };
+
+            if let TokenTree::Ident(ident) = &tt {
+                let is_delimiter = match self.base.buf.display_as_str(ident) {
+                    "if" => !self.is_expr_start(start),
+                    "else" => true,
+                    _ => false,
+                };
+
+                if is_delimiter {
+                    return Some(Err(tt.span()));
+                }
+            }
+
+            self.push_expr(start, &mut generics, tt)?;
+        }
+    }
+
+    /// Test if the next token of the expression which started at `start`
+    /// would start a new expression, which is the case if it's the first
+    /// token or if it follows an operator.
+    fn is_expr_start(&self, start: usize) -> bool {
+        match self.base.get(start..).and_then(|tokens| tokens.last()) {
+            Some(TokenTree::Punct(p)) => !matches!(p.as_char(), '?' | '>'),
+            Some(..) => false,
+            None => true,
+        }
+    }
+
+    /// Push the next token of the expression which started at `start`.
+    ///
+    /// An `if` in a position where it starts an expression is parsed together
+    /// with its blocks, since its condition and `else` branches otherwise end
+    /// the branch expression.
+    fn push_expr(&mut self, start: usize, generics: &mut Generics, tt: TokenTree) -> Option<()> {
+        if let TokenTree::Ident(ident) = &tt {
+            if self.base.buf.display_as_str(ident) == "if" && self.is_expr_start(start) {
+                let ident = ident.clone();
+                self.base.push(tt);
+                return self.parse_if_expr(ident);
+            }
+        }
+
+        generics.update(self.base.get(start..).unwrap_or_default(), &tt);
+        self.base.push(tt);
+        Some(())
+    }
+
+    /// Parse the remainder of an `if` expression like
+    /// `if <cond> { .. } else if <cond> { .. } else { .. }`, where the leading
+    /// `if` has already been consumed.
+    fn parse_if_expr(&mut self, mut ident: Ident) -> Option<()> {
+        loop {
+            let start = self.base.len();
+            let mut generics = Generics::default();
+
+            // The condition is followed by the first braced group.
+            loop {
+                let span = match self.base.peek_punct() {
+                    Some(p @ Punct { chars: ROCKET, .. }) if !generics.is_open() => Some(p.span),
+                    _ => None,
+                };
+
+                match (span, self.base.bump()) {
+                    (None, Some(TokenTree::Group(group)))
+                        if group.delimiter() == Delimiter::Brace && !generics.is_open() =>
+                    {
+                        self.base.push(TokenTree::Group(group));
+                        break;
+                    }
+                    (None, Some(tt)) => {
+                        self.push_expr(start, &mut generics, tt)?;
+                    }
+                    (span, _) => {
+                        self.errors.push(Error::new(
+                            span.unwrap_or_else(|| ident.span()),
+                            "expected block following `if` condition",
+                        ));
+                        self.recover_to_handler();
+                        return None;
+                    }
+                }
+            }
+
+            let tt = match self.base.nth(0) {
+                Some(TokenTree::Ident(ident)) => ident.clone(),
+                _ => return Some(()),
+            };
+
+            if self.base.buf.display_as_str(&tt) != "else" {
+                return Some(());
+            }
+
+            let _ = self.base.bump();
+            self.base.push(TokenTree::Ident(tt));
+
+            match self.base.bump() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    self.base.push(TokenTree::Group(group));
+                    return Some(());
+                }
+                Some(TokenTree::Ident(next)) if self.base.buf.display_as_str(&next) == "if" => {
+                    self.base.push(TokenTree::Ident(next.clone()));
+                    ident = next;
+                }
+                tt => {
+                    let span = tt.map(|tt| tt.span()).unwrap_or_else(|| ident.span());
+                    self.errors
+                        .push(Error::new(span, "expected block or `if` following `else`"));
+                    self.recover_to_handler();
+                    return None;
+                }
+            }
+        }
+    }
+
     /// Parse an else block.
     fn parse_else(&mut self) -> Option<Else> {
         let span = match self.base.peek_punct() {
@@ -352,28 +1267,208 @@ impl<'a> Parser<'a> {
         None
     }
 
+    /// Parse the `#[cfg(<predicate>)]` attributes preceding a segment.
+    ///
+    /// The predicates of multiple attributes are pushed as a comma-separated
+    /// list, so that they can be combined using `all(..)`.
+    fn parse_attributes(&mut self) -> Option<(Span, ops::Range<usize>)> {
+        let mut cfg = None::<(Span, ops::Range<usize>)>;
+
+        while let Some((TokenTree::Punct(p), TokenTree::Group(group))) = self.base.peek2() {
+            if p.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+                break;
+            }
+
+            let group = group.clone();
+            self.base.step(2);
+
+            let mut stream = group.stream().into_iter();
+
+            let predicate = match (stream.next(), stream.next(), stream.next()) {
+                (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(predicate)), None)
+                    if self.base.buf.display_as_str(&ident) == "cfg"
+                        && predicate.delimiter() == Delimiter::Parenthesis =>
+                {
+                    predicate
+                }
+                _ => {
+                    self.errors.push(Error::new(
+                        group.span(),
+                        "only `#[cfg(..)]` attributes are supported on branches",
+                    ));
+                    continue;
+                }
+            };
+
+            let start = self.base.len();
+            self.base.extend(predicate.stream());
+
+            if start == self.base.len() {
+                self.errors.push(Error::new(
+                    predicate.span(),
+                    "expected predicate in `#[cfg(..)]` attribute",
+                ));
+                continue;
+            }
+
+            self.base.push(TokenTree::Punct(proc_macro::Punct::new(
+                ',',
+                Spacing::Alone,
+            )));
+
+            match &mut cfg {
+                Some((_, range)) => {
+                    range.end = self.base.len();
+                }
+                None => {
+                    cfg = Some((group.span(), start..self.base.len()));
+                }
+            }
+        }
+
+        cfg
+    }
+
     /// Parse the next block, if present.
     fn parse_segment(&mut self, index: usize) -> Option<Segment> {
+        let cfg = self.parse_attributes();
+
+        match (self.parse_arm(index)?, cfg) {
+            (Segment::Branch(mut b), Some((_, range))) => {
+                b.cfg = Some(Cfg {
+                    var: format!("__cfg{index}").into(),
+                    range,
+                });
+
+                Some(Segment::Branch(b))
+            }
+            (segment, Some((span, _))) => {
+                self.errors.push(Error::new(
+                    span,
+                    "`#[cfg(..)]` attributes are only supported on branches",
+                ));
+
+                Some(segment)
+            }
+            (segment, None) => Some(segment),
+        }
+    }
+
+    /// Parse the next arm or branch, if present.
+    fn parse_arm(&mut self, index: usize) -> Option<Segment> {
         let start = self.base.len();
 
         if self.try_parse_else() {
             return Some(Segment::Else(self.parse_else()?));
         }
 
-        let binding = match self.parse_until_reserved(EQ) {
-            Ok(()) => start..self.base.len(),
-            Err(span) => {
-                self.errors
-                    .push(Error::new(span, "binding must be followed by a `=`"));
+        if let Some(ident) = self.try_parse_arm("timeout") {
+            return Some(Segment::Timeout(ident, self.parse_block()?));
+        }
 
-                self.recover_to_group();
-                return None;
+        if let Some(ident) = self.try_parse_arm("cancelled") {
+            return Some(Segment::Cancelled(ident, self.parse_block()?));
+        }
+
+        if let Some(ident) = self.try_parse_arm("complete") {
+            return Some(Segment::Complete(ident, self.parse_block()?));
+        }
+
+        if let Some(ident) = self.try_parse_arm("default") {
+            return Some(Segment::Default(ident, self.parse_block()?));
+        }
+
+        if let Some((ident, group)) = self.try_parse_panicked() {
+            let panicked = self.parse_panicked(&group);
+            let block = self.parse_block()?;
+            let (index, payload) = panicked?;
+            let panicked = Panicked {
+                index,
+                payload,
+                block,
+            };
+            return Some(Segment::Panicked(ident, panicked));
+        }
+
+        let mut modifiers = Modifiers::default();
+
+        let mut value = None;
+
+        let (kind, binding, expr, condition) = match self.try_parse_timer() {
+            Some((kind, group)) => {
+                let binding = start..start;
+
+                let expr = if let BranchKind::Send = kind {
+                    match self.parse_pair(&group) {
+                        Some((sender, v)) => {
+                            value = Some(v);
+                            sender
+                        }
+                        None => {
+                            self.errors.push(Error::new(
+                                group.span(),
+                                "expected `send(<sender>, <value>)`",
+                            ));
+                            self.recover_to_group();
+                            return None;
+                        }
+                    }
+                } else {
+                    let expr_start = self.base.len();
+                    self.base.extend(group.stream());
+                    expr_start..self.base.len()
+                };
+
+                let (rest, condition) = self.parse_expr(binding.end, true, &mut modifiers)?;
+
+                if !rest.is_empty() {
+                    let span = self.base.get(rest.start).map(|tt| tt.span());
+                    self.errors.push(Error::new(
+                        span.unwrap_or_else(|| group.span()),
+                        "expected `=>` following timer branch",
+                    ));
+                    let _ = self.parse_block();
+                    return None;
+                }
+
+                (kind, binding, expr, condition)
+            }
+            None => {
+                let binding = match self.parse_until_reserved(EQ) {
+                    Ok(()) => start..self.base.len(),
+                    Err(span) => {
+                        self.errors
+                            .push(Error::new(span, "binding must be followed by a `=`"));
+
+                        self.recover_to_group();
+                        return None;
+                    }
+                };
+
+                let (expr, condition) = self.parse_expr(binding.end, false, &mut modifiers)?;
+                (BranchKind::Future, binding, expr, condition)
             }
         };
 
-        let (expr, condition) = self.parse_expr(binding.end)?;
         let block = self.parse_block()?;
 
+        if let (Some(span), Some(..)) = (modifiers.repeat, &modifiers.retry) {
+            self.errors.push(Error::new(
+                span,
+                "`repeat` and `retry` modifiers can't be used together",
+            ));
+            return None;
+        }
+
+        if let (BranchKind::Send, Some(range)) = (&kind, &modifiers.batch) {
+            let span = self.base.get(range.start).map(|tt| tt.span());
+            self.errors.push(Error::new(
+                span.unwrap_or_else(Span::call_site),
+                "`batch` modifier can't be used with a `send` branch",
+            ));
+            return None;
+        }
+
         let condition = condition.map(|range| Condition {
             var: format!("__cond{index}").into(),
             range,
@@ -381,15 +1476,20 @@ impl<'a> Parser<'a> {
 
         let branch = Branch {
             index,
+            kind,
             binding,
             expr,
+            value,
             block,
             generic: format!("T{index}").into(),
             variant: format!("Branch{index}").into(),
             condition,
+            cfg: None,
+            modifiers,
+            on_timeout: None,
         };
 
-        Some(Segment::Branch(branch))
+        Some(Segment::Branch(Box::new(branch)))
     }
 
     /// Parse the next group.
@@ -418,6 +1518,105 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Tracks the angle brackets of generic arguments, like those of a turbofish
+/// or a qualified path, so that the punctuation they contain isn't mistaken
+/// for delimiters.
+#[derive(Default)]
+struct Generics {
+    depth: usize,
+}
+
+impl Generics {
+    /// Test if we're inside of generic arguments.
+    fn is_open(&self) -> bool {
+        self.depth > 0
+    }
+
+    /// Update the nesting with the next token `tt`, where `preceding` are the
+    /// tokens of the expression or pattern which precede it.
+    fn update(&mut self, preceding: &[TokenTree], tt: &TokenTree) {
+        let p = match tt {
+            TokenTree::Punct(p) => p,
+            _ => return,
+        };
+
+        match p.as_char() {
+            '<' if self.is_open() || opens_generics(preceding) => {
+                self.depth += 1;
+            }
+            // NB: The `>` of a `->` in something like `Fn() -> T` isn't a
+            // closing bracket.
+            '>' if self.is_open() && !is_joint(preceding, '-') => {
+                self.depth -= 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Test if a `<` following the given tokens opens generic arguments rather
+/// than being a comparison. This is only the case if it follows `::` as in a
+/// turbofish, the type of an `as` cast, or if it starts a qualified path at
+/// the beginning of the expression.
+fn opens_generics(preceding: &[TokenTree]) -> bool {
+    match preceding {
+        [] => true,
+        [.., TokenTree::Punct(..)] => is_path_sep(preceding),
+        [.., TokenTree::Ident(..)] => is_cast(preceding),
+        _ => false,
+    }
+}
+
+/// Test if the given tokens end with the path separator `::`.
+fn is_path_sep(tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [.., TokenTree::Punct(a), TokenTree::Punct(b)] => {
+            a.as_char() == ':' && a.spacing() == Spacing::Joint && b.as_char() == ':'
+        }
+        _ => false,
+    }
+}
+
+/// Test if the last of the given tokens is the joint punctuation `c`.
+fn is_joint(tokens: &[TokenTree], c: char) -> bool {
+    matches!(tokens.last(), Some(TokenTree::Punct(p)) if p.as_char() == c && p.spacing() == Spacing::Joint)
+}
+
+/// Test if the given tokens end with the path of a type in an `as` cast.
+fn is_cast(tokens: &[TokenTree]) -> bool {
+    for tt in tokens.iter().rev() {
+        match tt {
+            TokenTree::Ident(ident) if ident.to_string() == "as" => return true,
+            TokenTree::Ident(..) => {}
+            TokenTree::Punct(p) if p.as_char() == ':' => {}
+            _ => return false,
+        }
+    }
+
+    false
+}
+
+/// Error message for an option or modifier which requires a feature.
+fn requires_feature(what: &str, feature: &str) -> String {
+    format!("{what} requires the `{feature}` feature")
+}
+
+/// Test if the given group holds a literal zero, like `0` or `0usize`.
+///
+/// Other limits can be any expression, and are checked when the branch is
+/// constructed.
+fn is_zero(group: &Group) -> bool {
+    let mut it = group.stream().into_iter();
+
+    let literal = match (it.next(), it.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        _ => return false,
+    };
+
+    let suffix = literal.trim_start_matches(['0', '_']);
+    suffix.len() < literal.len() && (suffix.is_empty() || suffix.starts_with(['u', 'i']))
+}
+
 /// A branch condition.
 pub(crate) struct Condition {
     /// Condition variable.
@@ -426,14 +1625,75 @@ pub(crate) struct Condition {
     pub(crate) range: ops::Range<usize>,
 }
 
+/// The `#[cfg(..)]` attributes of a branch.
+pub(crate) struct Cfg {
+    /// Variable indicating if the branch is compiled in.
+    pub(crate) var: Box<str>,
+    /// Token range of the comma-separated predicates.
+    pub(crate) range: ops::Range<usize>,
+}
+
+/// The kind of a branch.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BranchKind {
+    /// A branch which polls a future.
+    Future,
+    /// A branch which completes once the given duration has elapsed, like
+    /// `after(<duration>)`.
+    After,
+    /// A branch which completes once the given deadline has been reached,
+    /// like `at(<deadline>)`.
+    At,
+    /// A branch which sends a value once a permit has been reserved, like
+    /// `send(<sender>, <value>)`.
+    Send,
+}
+
+impl BranchKind {
+    /// Indicate if the future of the branch is constructed through the timer.
+    pub(crate) fn uses_timer(self) -> bool {
+        matches!(self, BranchKind::After | BranchKind::At)
+    }
+}
+
+/// Modifiers applied to a branch, like `, timeout(<duration>)` or
+/// `, start_after(<duration>)`.
+#[derive(Default)]
+pub(crate) struct Modifiers {
+    /// Range for the duration after which the branch times out.
+    pub(crate) timeout: Option<ops::Range<usize>>,
+    /// Range for the duration after which the branch is started.
+    pub(crate) start_after: Option<ops::Range<usize>>,
+    /// Range for the number of times the branch is retried.
+    pub(crate) retry: Option<ops::Range<usize>>,
+    /// Range for the maximum number of outputs drained in a single batch.
+    pub(crate) batch: Option<ops::Range<usize>>,
+    /// Indicates that the branch is retried indefinitely.
+    pub(crate) repeat: Option<Span>,
+}
+
+impl Modifiers {
+    /// Test if the branch is retried when its pattern doesn't match.
+    pub(crate) fn is_retry(&self) -> bool {
+        self.retry.is_some() || self.repeat.is_some()
+    }
+}
+
 /// A regular branch.
 pub(crate) struct Branch {
     /// Branch index.
     pub(crate) index: usize,
-    /// Range for the binding to use.
+    /// The kind of the branch.
+    pub(crate) kind: BranchKind,
+    /// Range for the binding to use. This is empty for branches which do not
+    /// have a binding.
     pub(crate) binding: ops::Range<usize>,
-    /// Range for the expression to be evaluated as a future.
+    /// Range for the expression to be evaluated as a future, the argument to
+    /// a timer branch, or the sender of a send branch.
     pub(crate) expr: ops::Range<usize>,
+    /// Range for the value of a send branch, which is only evaluated once the
+    /// branch has completed.
+    pub(crate) value: Option<ops::Range<usize>>,
     /// Range for the branch.
     pub(crate) block: Block,
     /// The name of the generic used by the branch.
@@ -442,6 +1702,46 @@ pub(crate) struct Branch {
     pub(crate) variant: Box<str>,
     /// Branch condition.
     pub(crate) condition: Option<Condition>,
+    /// The `#[cfg(..)]` attributes of the branch.
+    pub(crate) cfg: Option<Cfg>,
+    /// Modifiers applied to the branch.
+    pub(crate) modifiers: Modifiers,
+    /// The `timeout =>` arm evaluated if the branch times out.
+    pub(crate) on_timeout: Option<OnTimeout>,
+}
+
+/// The `timeout =>` arm of a branch, which completes the select through its
+/// own variant of the output enum.
+pub(crate) struct OnTimeout {
+    /// The name of the enum variant used by the arm.
+    pub(crate) variant: Box<str>,
+    /// The block of the arm.
+    pub(crate) block: Block,
+}
+
+impl Branch {
+    /// Get the range of the tokens which construct a future using the timer,
+    /// if the branch uses one.
+    pub(crate) fn timer(&self) -> Option<ops::Range<usize>> {
+        match self.kind {
+            BranchKind::After | BranchKind::At => Some(self.expr.clone()),
+            _ => self
+                .modifiers
+                .timeout
+                .clone()
+                .or_else(|| self.modifiers.start_after.clone()),
+        }
+    }
+}
+
+/// Code for the `panicked(<index>, <payload>) =>` arm.
+pub(crate) struct Panicked {
+    /// Range for the pattern binding the index of the panicking branch.
+    pub(crate) index: ops::Range<usize>,
+    /// Range for the pattern binding the panic payload.
+    pub(crate) payload: ops::Range<usize>,
+    /// Range for the branch.
+    pub(crate) block: Block,
 }
 
 /// Code for the else branch.
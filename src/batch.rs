@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::branch::SelectBranch;
+
+/// A branch which drains up to a number of outputs from the branch it wraps
+/// every time it's polled. This is used by branches with the `batch(<n>)`
+/// modifier.
+pub struct Batch<T> {
+    branch: T,
+    limit: usize,
+}
+
+impl<T> Batch<T> {
+    pub(crate) fn new(limit: usize, branch: T) -> Self {
+        assert!(limit > 0, "batch limit must be greater than zero");
+        Self { branch, limit }
+    }
+}
+
+impl<T> SelectBranch for Batch<T>
+where
+    T: SelectBranch,
+{
+    type Output = Vec<T::Output>;
+
+    fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: Batch is safely pinned. The branch is structurally pinned,
+        // while the limit is not.
+        let (mut branch, limit) = unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            (Pin::new_unchecked(&mut this.branch), this.limit)
+        };
+
+        let mut items = Vec::new();
+
+        while items.len() < limit {
+            match branch.as_mut().poll_branch(cx) {
+                Poll::Ready(item) => {
+                    items.push(item);
+
+                    if branch.is_finished() {
+                        break;
+                    }
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        if items.is_empty() {
+            return Poll::Pending;
+        }
+
+        Poll::Ready(items)
+    }
+
+    #[inline]
+    fn is_finished(&self) -> bool {
+        self.branch.is_finished()
+    }
+
+    #[inline]
+    fn rearm(self: Pin<&mut Self>) -> bool {
+        // SAFETY: Batch is safely pinned.
+        unsafe { Pin::map_unchecked_mut(self, |this| &mut this.branch).rearm() }
+    }
+}
+
+impl<T> fmt::Debug for Batch<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batch")
+            .field("branch", &self.branch)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
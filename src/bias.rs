@@ -8,6 +8,10 @@ pub trait Bias<Bits> {
     /// Apply the bias to the given snapshot and construct an iterator over its
     /// items.
     fn apply(&self, snapshot: Set<Bits>) -> Self::Apply;
+
+    /// Redraw the bias, which is done when a select is reset.
+    #[inline]
+    fn redraw(&mut self) {}
 }
 
 /// An unbiased selector which starts from the top and works its way to the
@@ -50,6 +54,72 @@ where
             iter: Set::new(set.state().rotate_right(self.0)).iter(),
         }
     }
+
+    #[cfg(feature = "random")]
+    #[inline]
+    fn redraw(&mut self) {
+        self.0 = crate::rand::thread_rng_n(64);
+    }
+}
+
+/// A bias which always polls the branch with the given index first, and then
+/// applies the bias it wraps to the remaining branches.
+///
+/// This is used for the implicit cancellation branch added by the
+/// `cancel = <future>;` option.
+#[derive(Debug)]
+pub struct Prioritized<B> {
+    bias: B,
+    index: u32,
+}
+
+impl<B> Prioritized<B> {
+    pub(crate) const fn new(bias: B, index: u32) -> Self {
+        Self { bias, index }
+    }
+}
+
+impl<Bits, B> Bias<Bits> for Prioritized<B>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+{
+    type Apply = PrioritizedIter<B::Apply>;
+
+    fn apply(&self, mut snapshot: Set<Bits>) -> Self::Apply {
+        let first = snapshot.contains(self.index).then_some(self.index);
+        snapshot.clear(self.index);
+
+        PrioritizedIter {
+            first,
+            iter: self.bias.apply(snapshot),
+        }
+    }
+
+    #[inline]
+    fn redraw(&mut self) {
+        self.bias.redraw();
+    }
+}
+
+#[derive(Debug)]
+pub struct PrioritizedIter<I> {
+    first: Option<u32>,
+    iter: I,
+}
+
+impl<I> Iterator for PrioritizedIter<I>
+where
+    I: Iterator<Item = u32>,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.first.take() {
+            Some(index) => Some(index),
+            None => self.iter.next(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -75,7 +145,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{Bias, Random};
+    use super::{Bias, Prioritized, Random, Unbiased};
     use crate::set::Set;
 
     #[test]
@@ -99,4 +169,26 @@ mod tests {
         assert_eq!(it.next(), Some(10));
         assert_eq!(it.next(), None);
     }
+
+    #[test]
+    fn test_prioritized_bias() {
+        let set = Set::new(2u32 + 64 + 128 + 1024);
+        let prioritized = Prioritized::new(Random::new(3), 10);
+        let mut it = prioritized.apply(set);
+
+        assert_eq!(it.next(), Some(10));
+        assert_eq!(it.next(), Some(6));
+        assert_eq!(it.next(), Some(7));
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), None);
+
+        let prioritized = Prioritized::new(Unbiased, 5);
+        let mut it = prioritized.apply(set);
+
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), Some(6));
+        assert_eq!(it.next(), Some(7));
+        assert_eq!(it.next(), Some(10));
+        assert_eq!(it.next(), None);
+    }
 }
@@ -0,0 +1,102 @@
+use alloc::alloc::{alloc, handle_alloc_error, Layout};
+use alloc::boxed::Box;
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::pin::Pin;
+use core::ptr::{self, NonNull};
+
+/// Boxed state of a select which is initialized one field at a time. Each field
+/// is still evaluated on the stack before being written into the box, but the
+/// whole state is never constructed there. This is used by the `boxed;` option.
+///
+/// If initializing a field panics, `drop` is called with the number of fields
+/// which have been initialized so far and the allocation is released.
+pub struct BoxedInit<S, D>
+where
+    D: FnMut(*mut S, usize),
+{
+    state: Box<MaybeUninit<S>>,
+    initialized: usize,
+    drop: D,
+}
+
+impl<S, D> BoxedInit<S, D>
+where
+    D: FnMut(*mut S, usize),
+{
+    pub(crate) fn new(drop: D) -> Self {
+        let layout = Layout::new::<S>();
+
+        // NB: The box is allocated directly, since `Box::new` might construct
+        // the uninitialized value on the stack first.
+        let state = if layout.size() == 0 {
+            NonNull::<MaybeUninit<S>>::dangling()
+        } else {
+            // SAFETY: The layout has a non-zero size.
+            match NonNull::new(unsafe { alloc(layout) }) {
+                Some(state) => state.cast(),
+                None => handle_alloc_error(layout),
+            }
+        };
+
+        // SAFETY: The pointer was allocated with the layout of `S`, or is
+        // dangling if it's zero-sized.
+        let state = unsafe { Box::from_raw(state.as_ptr()) };
+
+        Self {
+            state,
+            initialized: 0,
+            drop,
+        }
+    }
+
+    /// Get a pointer to the state being initialized.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut S {
+        self.state.as_mut_ptr()
+    }
+
+    /// Mark the next field as initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have initialized the next field in declaration order.
+    #[inline]
+    pub unsafe fn initialized(&mut self) {
+        self.initialized += 1;
+    }
+
+    /// Finish initializing the state.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have initialized every field of the state.
+    #[inline]
+    pub unsafe fn finish(self) -> Pin<Box<S>> {
+        let this = ManuallyDrop::new(self);
+        let state = Box::into_raw(ptr::read(&this.state));
+        Box::into_pin(Box::from_raw(state.cast::<S>()))
+    }
+}
+
+impl<S, D> Drop for BoxedInit<S, D>
+where
+    D: FnMut(*mut S, usize),
+{
+    fn drop(&mut self) {
+        // NB: The allocation is released when the box is dropped, even if
+        // dropping a field panics.
+        (self.drop)(self.state.as_mut_ptr(), self.initialized);
+    }
+}
+
+impl<S, D> fmt::Debug for BoxedInit<S, D>
+where
+    D: FnMut(*mut S, usize),
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedInit")
+            .field("initialized", &self.initialized)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,193 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// The kind of value which can be polled by a branch in a
+/// [select!][crate::select!] or [inline!][crate::inline!].
+///
+/// This is implemented for every [Future], which completes once and is then
+/// disabled. Other types can implement it to provide branches which produce
+/// many values, or which can be re-armed once their output didn't match the
+/// pattern of the branch.
+///
+/// # Examples
+///
+/// ```
+/// use std::pin::Pin;
+/// use std::task::{Context, Poll};
+///
+/// use selectme::SelectBranch;
+///
+/// /// A branch which is ready a fixed number of times.
+/// struct Countdown(u32);
+///
+/// impl SelectBranch for Countdown {
+///     type Output = u32;
+///
+///     fn poll_branch(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+///         self.0 -= 1;
+///         Poll::Ready(self.0)
+///     }
+///
+///     fn is_finished(&self) -> bool {
+///         self.0 == 0
+///     }
+/// }
+///
+/// # #[selectme::main] async fn main() {
+/// let output = selectme::inline! {
+///     n = Countdown(3) => Some(n),
+///     else => None,
+/// };
+///
+/// tokio::pin!(output);
+///
+/// let mut values = Vec::new();
+///
+/// while let Some(n) = output.as_mut().next().await {
+///     values.push(n);
+/// }
+///
+/// assert_eq!(values, [2, 1, 0]);
+/// # }
+/// ```
+pub trait SelectBranch {
+    /// The output of the branch, which is matched against its pattern.
+    type Output;
+
+    /// Poll the branch for its next output.
+    fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output>;
+
+    /// Test if the branch is finished once it has produced an output, in
+    /// which case it is disabled. Branches which aren't finished are polled
+    /// again.
+    #[inline]
+    fn is_finished(&self) -> bool {
+        true
+    }
+
+    /// Re-arm a branch whose output didn't match its pattern, returning a
+    /// boolean indicating if it should be polled again.
+    #[inline]
+    fn rearm(self: Pin<&mut Self>) -> bool {
+        false
+    }
+}
+
+impl<F> SelectBranch for F
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    #[inline]
+    fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Future::poll(self, cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+mod stream {
+    use core::fmt;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+
+    use futures_core::Stream;
+
+    use crate::branch::SelectBranch;
+    use crate::cancel_safe::CancelSafe;
+
+    /// A branch which polls the next item from a [Stream].
+    ///
+    /// The branch produces `Some(item)` for every item in the stream, and
+    /// `None` once the stream has ended after which the branch is finished.
+    /// It's polled again after every item, so it can be combined with the
+    /// `batch(<n>)` modifier to drain a stream in batches.
+    ///
+    /// The branch is only [cancellation safe][CancelSafe] if the stream is,
+    /// such as when it's borrowed, since an owned stream is dropped together
+    /// with any items it has buffered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use selectme::StreamBranch;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     items = StreamBranch::new(tokio_stream::iter([1, 2, 3])), batch(2) => Some(items),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// assert_eq!(output.as_mut().next().await, Some(vec![Some(1), Some(2)]));
+    /// assert_eq!(output.as_mut().next().await, Some(vec![Some(3), None]));
+    /// assert_eq!(output.as_mut().next().await, None);
+    /// # }
+    /// ```
+    pub struct StreamBranch<S> {
+        stream: S,
+        finished: bool,
+    }
+
+    impl<S> StreamBranch<S> {
+        /// Construct a branch polling items from `stream`.
+        #[inline]
+        pub fn new(stream: S) -> Self {
+            Self {
+                stream,
+                finished: false,
+            }
+        }
+    }
+
+    impl<S> SelectBranch for StreamBranch<S>
+    where
+        S: Stream,
+    {
+        type Output = Option<S::Item>;
+
+        #[inline]
+        fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            // SAFETY: The stream is structurally pinned, while the flag is not.
+            let (stream, finished) = unsafe {
+                let this = Pin::get_unchecked_mut(self);
+                (Pin::new_unchecked(&mut this.stream), &mut this.finished)
+            };
+
+            let item = match stream.poll_next(cx) {
+                Poll::Ready(item) => item,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            *finished = item.is_none();
+            Poll::Ready(item)
+        }
+
+        #[inline]
+        fn is_finished(&self) -> bool {
+            self.finished
+        }
+    }
+
+    impl<S> fmt::Debug for StreamBranch<S>
+    where
+        S: fmt::Debug,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("StreamBranch")
+                .field("stream", &self.stream)
+                .field("finished", &self.finished)
+                .finish()
+        }
+    }
+
+    // NB: Items which haven't been polled yet are retained by the stream, so
+    // this only holds if the stream isn't dropped with them, like when it's
+    // borrowed.
+    impl<S> CancelSafe for StreamBranch<S> where S: CancelSafe {}
+}
+
+#[cfg(feature = "futures")]
+pub use self::stream::StreamBranch;
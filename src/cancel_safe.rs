@@ -0,0 +1,165 @@
+use core::future::{Pending, Ready};
+use core::pin::Pin;
+
+/// Marker trait for futures which are cancellation safe.
+///
+/// A future is cancellation safe if dropping it before it completes doesn't
+/// lose any data, such as a message which has been received but not yet
+/// returned. Such futures can safely be constructed again in every iteration
+/// of a loop which uses [select!][crate::select!].
+///
+/// With the `cancel_safe;` option, every branch future in a select has to
+/// implement this trait. Borrowed futures are always cancellation safe, since
+/// they're not dropped when the select completes.
+///
+/// # Examples
+///
+/// ```
+/// use std::future::{pending, ready};
+///
+/// # #[selectme::main] async fn main() {
+/// let mut long = pending::<u32>();
+///
+/// for n in 0..3 {
+///     let output = selectme::select! {
+///         cancel_safe;
+///
+///         value = &mut long => value,
+///         value = ready(n) => value,
+///     };
+///
+///     assert_eq!(output, n);
+/// }
+/// # }
+/// ```
+pub trait CancelSafe {}
+
+impl<T> CancelSafe for Ready<T> {}
+
+impl<T> CancelSafe for Pending<T> {}
+
+impl<F> CancelSafe for &mut F where F: ?Sized {}
+
+impl<F> CancelSafe for Pin<&mut F> where F: ?Sized {}
+
+#[cfg(feature = "alloc")]
+impl<F> CancelSafe for alloc::boxed::Box<F> where F: ?Sized + CancelSafe {}
+
+#[cfg(feature = "alloc")]
+impl<F> CancelSafe for Pin<alloc::boxed::Box<F>> where F: ?Sized + CancelSafe {}
+
+#[cfg(feature = "test-util")]
+impl CancelSafe for crate::timer::MockSleep {}
+
+#[cfg(feature = "tokio")]
+pub use self::tokio_impls::{Recv, UnboundedRecv};
+
+#[cfg(feature = "tokio")]
+mod tokio_impls {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+
+    use tokio::sync::mpsc::{Receiver, UnboundedReceiver};
+    use tokio::time::{Sleep, Timeout};
+
+    use super::CancelSafe;
+
+    impl CancelSafe for Sleep {}
+
+    impl<F> CancelSafe for Timeout<F> where F: CancelSafe {}
+
+    /// A cancellation safe future receiving a message from a
+    /// [Receiver][tokio::sync::mpsc::Receiver].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio::sync::mpsc;
+    ///
+    /// use selectme::Recv;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let (tx, mut rx) = mpsc::channel(1);
+    /// tx.send(42).await.unwrap();
+    ///
+    /// let output = selectme::select! {
+    ///     cancel_safe;
+    ///
+    ///     value = Recv::new(&mut rx) => value,
+    /// };
+    ///
+    /// assert_eq!(output, Some(42));
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct Recv<'a, T> {
+        receiver: &'a mut Receiver<T>,
+    }
+
+    impl<'a, T> Recv<'a, T> {
+        /// Construct a future receiving the next message from `receiver`.
+        #[inline]
+        pub fn new(receiver: &'a mut Receiver<T>) -> Self {
+            Self { receiver }
+        }
+    }
+
+    impl<T> Future for Recv<'_, T> {
+        type Output = Option<T>;
+
+        #[inline]
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.receiver.poll_recv(cx)
+        }
+    }
+
+    impl<T> CancelSafe for Recv<'_, T> {}
+
+    /// A cancellation safe future receiving a message from an
+    /// [UnboundedReceiver][tokio::sync::mpsc::UnboundedReceiver].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tokio::sync::mpsc;
+    ///
+    /// use selectme::UnboundedRecv;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let (tx, mut rx) = mpsc::unbounded_channel();
+    /// tx.send(42).unwrap();
+    ///
+    /// let output = selectme::select! {
+    ///     cancel_safe;
+    ///
+    ///     value = UnboundedRecv::new(&mut rx) => value,
+    /// };
+    ///
+    /// assert_eq!(output, Some(42));
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct UnboundedRecv<'a, T> {
+        receiver: &'a mut UnboundedReceiver<T>,
+    }
+
+    impl<'a, T> UnboundedRecv<'a, T> {
+        /// Construct a future receiving the next message from `receiver`.
+        #[inline]
+        pub fn new(receiver: &'a mut UnboundedReceiver<T>) -> Self {
+            Self { receiver }
+        }
+    }
+
+    impl<T> Future for UnboundedRecv<'_, T> {
+        type Output = Option<T>;
+
+        #[inline]
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            self.receiver.poll_recv(cx)
+        }
+    }
+
+    impl<T> CancelSafe for UnboundedRecv<'_, T> {}
+}
@@ -0,0 +1,46 @@
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// A future which polls the future it wraps once, and resolves to [`None`] if
+/// it isn't ready. This is used to implement the `default` arm of the
+/// `compat(futures)` option.
+pub struct NowOrNever<F> {
+    future: F,
+}
+
+impl<F> NowOrNever<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self { future }
+    }
+}
+
+impl<F> Future for NowOrNever<F>
+where
+    F: Future,
+{
+    type Output = Option<F::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: NowOrNever is safely pinned, and the future is structurally
+        // pinned.
+        let future = unsafe { Pin::map_unchecked_mut(self, |this| &mut this.future) };
+
+        match future.poll(cx) {
+            Poll::Ready(output) => Poll::Ready(Some(output)),
+            Poll::Pending => Poll::Ready(None),
+        }
+    }
+}
+
+impl<F> fmt::Debug for NowOrNever<F>
+where
+    F: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NowOrNever")
+            .field("future", &self.future)
+            .finish()
+    }
+}
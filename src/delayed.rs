@@ -0,0 +1,97 @@
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::branch::SelectBranch;
+
+/// A branch future which is only constructed once the given sleep has
+/// completed. This is used by branches with the `start_after(<duration>)`
+/// modifier.
+///
+/// The factory is passed a reference to the given context, which holds the
+/// timer if the future is constructed through one.
+pub struct Delayed<S, C, F, T> {
+    state: State<S, C, F, T>,
+}
+
+enum State<S, C, F, T> {
+    Waiting(S, Option<(C, F)>),
+    Active(T),
+}
+
+impl<S, C, F, T> Delayed<S, C, F, T> {
+    pub(crate) fn new(sleep: S, context: C, factory: F) -> Self {
+        Self {
+            state: State::Waiting(sleep, Some((context, factory))),
+        }
+    }
+}
+
+impl<S, C, F, T> SelectBranch for Delayed<S, C, F, T>
+where
+    S: Future<Output = ()>,
+    F: FnOnce(&C) -> T,
+    T: SelectBranch,
+{
+    type Output = T::Output;
+
+    fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: Delayed is safely pinned. The sleep and the future are
+        // structurally pinned, while the factory is not.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+
+            if let State::Waiting(sleep, factory) = &mut this.state {
+                if Pin::new_unchecked(sleep).poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+
+                let (context, factory) = match factory.take() {
+                    Some(factory) => factory,
+                    None => unreachable!("delayed future already constructed"),
+                };
+
+                // NB: This drops the pinned sleep in place.
+                this.state = State::Active(factory(&context));
+            }
+
+            match &mut this.state {
+                State::Active(future) => Pin::new_unchecked(future).poll_branch(cx),
+                State::Waiting(..) => unreachable!("delayed future not constructed"),
+            }
+        }
+    }
+
+    #[inline]
+    fn is_finished(&self) -> bool {
+        match &self.state {
+            State::Active(future) => future.is_finished(),
+            State::Waiting(..) => false,
+        }
+    }
+
+    #[inline]
+    fn rearm(self: Pin<&mut Self>) -> bool {
+        // SAFETY: Delayed is safely pinned.
+        unsafe {
+            match &mut Pin::get_unchecked_mut(self).state {
+                State::Active(future) => Pin::new_unchecked(future).rearm(),
+                State::Waiting(..) => false,
+            }
+        }
+    }
+}
+
+impl<S, C, F, T> fmt::Debug for Delayed<S, C, F, T>
+where
+    S: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.state {
+            State::Waiting(sleep, _) => f.debug_tuple("Waiting").field(sleep).finish(),
+            State::Active(future) => f.debug_tuple("Active").field(future).finish(),
+        }
+    }
+}
@@ -174,8 +174,36 @@
 #![deny(unreachable_pub)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod batch;
+
+#[cfg(feature = "alloc")]
+mod boxed;
+
 mod bias;
-pub use self::bias::{Random, Unbiased};
+pub use self::bias::{Prioritized, Random, Unbiased};
+
+mod branch;
+pub use self::branch::SelectBranch;
+#[cfg(feature = "futures")]
+pub use self::branch::StreamBranch;
+
+mod cancel_safe;
+pub use self::cancel_safe::CancelSafe;
+#[cfg(feature = "tokio")]
+pub use self::cancel_safe::{Recv, UnboundedRecv};
+
+mod compat;
+
+mod delayed;
+
+#[cfg(feature = "alloc")]
+mod quorum;
+
+mod retry;
 
 #[cfg(feature = "random")]
 mod rand;
@@ -183,11 +211,29 @@ mod rand;
 mod select;
 pub use crate::select::Select;
 
+mod select_send;
+pub use self::select_send::{SelectSend, SendPermit, Sending};
+#[cfg(feature = "tokio")]
+pub use self::select_send::{SenderReserve, SenderReserveOwned};
+
 mod static_select;
-pub use crate::static_select::StaticSelect;
+pub use crate::static_select::{PollFn, Poller, StaticSelect};
 
 mod set;
 
+mod slot;
+pub use self::slot::Slot;
+
+mod state;
+pub use self::state::StateField;
+
+mod timer;
+pub use self::timer::Timer;
+#[cfg(feature = "tokio")]
+pub use self::timer::TokioTimer;
+#[cfg(feature = "test-util")]
+pub use self::timer::{MockSleep, MockTimer};
+
 #[macro_use]
 mod macros;
 
@@ -197,10 +243,29 @@ pub use ::selectme_macros::{main, test};
 /// Hidden support module used by macros.
 #[doc(hidden)]
 pub mod __support {
-    pub use crate::bias::{Bias, Random, Unbiased};
+    #[cfg(feature = "alloc")]
+    pub use crate::batch::Batch;
+    pub use crate::bias::{Bias, Prioritized, Random, Unbiased};
+    #[cfg(feature = "alloc")]
+    pub use crate::boxed::BoxedInit;
+    pub use crate::branch::SelectBranch;
+    pub use crate::cancel_safe::CancelSafe;
+    pub use crate::compat::NowOrNever;
+    pub use crate::delayed::Delayed;
+    #[cfg(feature = "alloc")]
+    pub use crate::quorum::{Control, Quorum, QuorumSelect};
+    pub use crate::retry::Retry;
     pub use crate::select::DISABLED;
+    pub use crate::select_send::{IntoPermit, Reserve, SelectSend, SendPermit, Sending};
+    pub use crate::slot::{DropGuard, Slot};
+    pub use crate::static_select::PollFn;
+    pub use crate::timer::Timer;
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec::Vec;
+    pub use core::clone::Clone;
     pub use core::future::Future;
     pub use core::pin::Pin;
+    pub use core::ptr::{addr_of_mut, drop_in_place, write};
     pub use core::task::Poll;
     pub use selectme_macros::{inline, select};
 
@@ -208,6 +273,7 @@ pub mod __support {
 
     use crate::select::Select;
     use crate::set::{Number, Set};
+    use crate::slot::{DropFn, State};
     use crate::static_select::StaticSelect;
 
     /// Construct a random bias.
@@ -217,35 +283,282 @@ pub mod __support {
         Random::new(crate::rand::thread_rng_n(64))
     }
 
+    /// Construct the default timer.
+    #[inline]
+    #[cfg(feature = "tokio")]
+    pub const fn default_timer() -> crate::timer::TokioTimer {
+        crate::timer::TokioTimer
+    }
+
     /// Construct an unbiased bias.
     #[inline]
     pub const fn unbiased() -> Unbiased {
         Unbiased
     }
 
+    /// Construct a bias which always polls the branch with the given `index`
+    /// first.
+    #[inline]
+    pub const fn prioritized<B>(bias: B, index: u32) -> Prioritized<B> {
+        Prioritized::new(bias, index)
+    }
+
+    /// Test if the fused future of a branch has terminated.
+    #[inline]
+    #[cfg(feature = "futures")]
+    pub fn is_terminated<F>(future: &F) -> bool
+    where
+        F: ?Sized + futures_core::FusedFuture,
+    {
+        future.is_terminated()
+    }
+
+    /// Construct a future which polls `future` once, resolving to `None` if
+    /// it isn't ready.
+    #[inline]
+    pub fn now_or_never<F>(future: F) -> NowOrNever<F>
+    where
+        F: Future,
+    {
+        NowOrNever::new(future)
+    }
+
+    /// Construct a branch future which is only constructed through `factory`
+    /// once `sleep` has completed. The factory is passed a reference to
+    /// `context`.
+    #[inline]
+    pub fn delayed<S, C, F, T>(sleep: S, context: C, factory: F) -> Delayed<S, C, F, T>
+    where
+        S: Future<Output = ()>,
+        F: FnOnce(&C) -> T,
+        T: SelectBranch,
+    {
+        Delayed::new(sleep, context, factory)
+    }
+
+    /// Construct a branch future which can be rebuilt through `factory` up to
+    /// `attempts` times, or indefinitely if `attempts` is `None`. The factory
+    /// is passed a reference to `context`.
+    #[inline]
+    pub fn retry<C, F, T>(attempts: Option<usize>, context: C, factory: F) -> Retry<C, F, T>
+    where
+        F: FnMut(&C) -> T,
+        T: SelectBranch,
+    {
+        Retry::new(attempts, context, factory)
+    }
+
+    /// Construct a branch which drains up to `limit` outputs from `branch`
+    /// every time it's polled.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn batch<T>(limit: usize, branch: T) -> Batch<T>
+    where
+        T: SelectBranch,
+    {
+        Batch::new(limit, branch)
+    }
+
+    /// Construct the future of a send branch in `select!`, which reserves a
+    /// permit using `future`.
+    #[inline]
+    pub fn reserve<F>(future: F) -> Reserve<F>
+    where
+        F: Future,
+        F::Output: IntoPermit,
+    {
+        Reserve::new(future)
+    }
+
+    /// Construct the future of a send branch in `inline!`, which sends `value`
+    /// once `future` has reserved a permit.
+    #[inline]
+    pub fn sending<F, T>(future: F, value: T) -> Sending<F, T>
+    where
+        F: Future,
+        F::Output: IntoPermit,
+        <F::Output as IntoPermit>::Permit: SendPermit<Item = T>,
+    {
+        Sending::new(future, value)
+    }
+
+    /// Construct a future which polls `select` until `quorum` of its branches
+    /// have completed, where `branch` identifies the branch of an output.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn quorum<T, F>(quorum: usize, select: T, branch: F) -> Quorum<T, F, T::Output>
+    where
+        T: QuorumSelect,
+        F: Fn(&T::Output) -> Option<u32>,
+    {
+        Quorum::new(quorum, select, branch)
+    }
+
+    /// Allocate the boxed state of a select, which is initialized in place.
+    /// If initialization is interrupted, `drop` drops the given number of
+    /// fields which have been initialized.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn boxed<S, D>(drop: D) -> BoxedInit<S, D>
+    where
+        D: FnMut(*mut S, usize),
+    {
+        BoxedInit::new(drop)
+    }
+
+    /// Project boxed state into the pinned state it contains.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn project_boxed<S>(state: Pin<&mut Pin<alloc::boxed::Box<S>>>) -> Pin<&mut S> {
+        Pin::get_mut(state).as_mut()
+    }
+
+    /// Drop boxed state, where `drop` drops the fields of the state in place
+    /// and the allocation is released afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The state must not be used again, and `drop` must drop every field
+    /// which is initialized.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub unsafe fn drop_boxed<S, F>(state: Pin<&mut Pin<alloc::boxed::Box<S>>>, drop: F)
+    where
+        F: FnOnce(&mut S),
+    {
+        use alloc::boxed::Box;
+        use core::mem::ManuallyDrop;
+
+        let state = core::ptr::read(Pin::get_unchecked_mut(state));
+        let state = Box::into_raw(Pin::into_inner_unchecked(state));
+        // NB: The allocation is released even if dropping a field panics.
+        let mut state = Box::from_raw(state.cast::<ManuallyDrop<S>>());
+        drop(&mut state);
+    }
+
+    /// Assert that the future of a branch is `Send`.
+    #[inline(always)]
+    pub fn assert_send<T>(future: T) -> T
+    where
+        T: Send,
+    {
+        future
+    }
+
+    /// Assert that the future of a branch is cancellation safe.
+    #[inline(always)]
+    pub fn assert_cancel_safe<T>(future: T) -> T
+    where
+        T: CancelSafe,
+    {
+        future
+    }
+
+    /// The payload of a panic caught with the `catch_unwind` option.
+    #[cfg(feature = "std")]
+    pub type Payload = std::boxed::Box<dyn std::any::Any + Send + 'static>;
+
+    /// Poll a branch, catching any panic.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn poll_unwind<F>(
+        branch: Pin<&mut F>,
+        cx: &mut Context<'_>,
+    ) -> Result<Poll<F::Output>, Payload>
+    where
+        F: SelectBranch,
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| branch.poll_branch(cx)))
+    }
+
+    /// Construct the slot of a conditional branch whose precondition is
+    /// true.
+    #[inline]
+    pub const fn slot<T>(value: T) -> Slot<T> {
+        Slot::new(value)
+    }
+
+    /// Project a pinned slot into its value.
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized.
+    #[inline]
+    pub unsafe fn project_slot<T>(slot: Pin<&mut Slot<T>>) -> Pin<&mut T> {
+        Slot::project(slot)
+    }
+
+    /// Project a pinned branch future and its timeout.
+    #[inline]
+    pub fn project_timeout<F, T>(pair: Pin<&mut (F, T)>) -> (Pin<&mut F>, Pin<&mut T>) {
+        // SAFETY: The pair is structurally pinned, neither of its fields are
+        // moved.
+        unsafe {
+            let (fut, timeout) = Pin::get_unchecked_mut(pair);
+            (Pin::new_unchecked(fut), Pin::new_unchecked(timeout))
+        }
+    }
+
     /// Setup a [Select] with a dynamic function used to poll.
+    ///
+    /// If the select has conditional branches, `drop` is used to drop its
+    /// state given the set of branches which are initialized.
     #[inline]
-    pub fn select<Bits, S, B, T, O>(mask: Bits, bias: B, state: S, poll: T) -> Select<Bits, S, B, T>
+    pub fn select<Bits, S, B, T, O>(
+        mask: Bits,
+        bias: B,
+        state: S,
+        drop: Option<DropFn<Bits, S>>,
+        poll: T,
+    ) -> Select<Bits, S, B, T>
     where
         Bits: Number,
         B: Bias<Bits>,
         T: FnMut(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
     {
+        let state = State::new(Set::new(mask), state, drop);
         Select::new(Set::new(mask), bias, state, poll)
     }
 
-    /// Setup a [Select] with a static function used to poll.
+    /// Setup a [StaticSelect] with a statically dispatched poller.
+    ///
+    /// The `unconditional` mask contains the branches which don't have a
+    /// precondition, which are the ones enabled when the select is reset.
+    ///
+    /// The poller is required to be `'static`, which since it's never a `move`
+    /// closure means that it can't capture its environment.
     #[inline]
-    pub fn static_select<Bits, S, B, O>(
+    pub fn static_select<Bits, S, B, P, C, O>(
         mask: Bits,
+        unconditional: Bits,
         bias: B,
         state: S,
-        poll: fn(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
-    ) -> StaticSelect<Bits, S, B, O>
+        drop: Option<DropFn<Bits, S>>,
+        poll: P,
+    ) -> StaticSelect<Bits, S, B, O, P>
     where
         Bits: Number,
         B: Bias<Bits>,
+        P: 'static + Fn(&mut Context<'_>, &mut C, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
+    {
+        let state = State::new(Set::new(mask), state, drop);
+        StaticSelect::new(Set::new(mask), Set::new(unconditional), bias, state, poll)
+    }
+
+    /// Check the poller of a [StaticSelect] and return the select.
+    ///
+    /// The poller generated by the `static;` option is coerced into a
+    /// [PollFn] by `check`, so that a handler which captures its environment is
+    /// reported as such and not as a poller which doesn't outlive `'static`.
+    #[inline]
+    pub fn check_poller<Bits, S, B, O, P, F>(
+        select: StaticSelect<Bits, S, B, O, P>,
+        check: F,
+    ) -> StaticSelect<Bits, S, B, O, P>
+    where
+        F: FnOnce(&P),
     {
-        StaticSelect::new(Set::new(mask), bias, state, poll)
+        check(select.poller());
+        select
     }
 }
@@ -23,10 +23,15 @@
 /// returns the result of evaluating the completed branch's `<handler>`
 /// expression.
 ///
+/// An `<async expression>` is usually a future, but it can be anything which
+/// implements [SelectBranch][crate::SelectBranch], such as a source which
+/// produces many values.
+///
 /// Additionally, each branch may include an optional `if` precondition. If the
-/// precondition returns `false`, then the branch is disabled. The provided
-/// `<async expression>` is still evaluated but the resulting future is never
-/// polled. This capability is useful when using `select!` within a loop.
+/// precondition returns `false`, then the branch is disabled and the provided
+/// `<async expression>` is never evaluated. This capability is useful when
+/// using `select!` within a loop. Branches with a precondition are stored in a
+/// [Slot][crate::Slot], which takes up no more space than the future itself.
 ///
 /// The complete lifecycle of a `select!` expression is as follows:
 ///
@@ -40,12 +45,561 @@
 /// 3. Concurrently await on the results for all remaining `<async
 ///    expression>`s.
 /// 4. Once an `<async expression>` returns a value, attempt to apply the value
-///    to the provided `<pattern>` if the pattern matches, evaluate `<handler>`
-///    and return. If the pattern **does not** match, disable the current branch
-///    and for the remainder of the current call to `select!`. Continue from
-///    step 3.
-/// 5. If **all** branches are disabled, evaluate the `else` expression. If no
-///    else branch is provided, panic.
+///    to the provided `<pattern>` if the pattern matches, drop all branch
+///    futures, evaluate `<handler>` and return. If the pattern **does not**
+///    match, disable the current branch and for the remainder of the current
+///    call to `select!`. Continue from step 3.
+/// 5. If **all** branches are disabled, drop all branch futures and evaluate
+///    the `else` expression. If no else branch is provided, panic. Selections
+///    produced by [inline!][crate::inline!] can instead be drained without an
+///    `else` branch through [Select::try_next][crate::Select::try_next].
+///
+/// Branch futures which are still alive are dropped in the order in which
+/// their branches are declared, and this always happens *before* the handler
+/// is evaluated. So any locks, permits or borrows held by the branches which
+/// did not complete are released by the time the handler runs.
+///
+/// # Timer branches
+///
+/// Instead of a `<pattern> = <async expression>` a branch can be declared as a
+/// timer, which completes once a duration has elapsed or once a deadline has
+/// been reached:
+///
+/// ```text
+/// after(<duration>) (if <precondition>)? => <handler>,
+/// at(<deadline>) (if <precondition>)? => <handler>,
+/// ```
+///
+/// The futures for these branches are constructed through a
+/// [Timer][crate::Timer], which is specified with the `timer = <expr>;`
+/// option. If the `tokio` feature is enabled this defaults to
+/// [`TokioTimer`], in which case `<duration>` is a
+/// [`Duration`][core::time::Duration] and `<deadline>` is a
+/// [`tokio::time::Instant`]. Otherwise a timer has to be specified, and
+/// specifying one for a select where no branch uses it is an error.
+///
+/// The [`MockTimer`] used below is only available with the
+/// `test-util` feature.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use selectme::MockTimer;
+///
+/// # #[selectme::main] async fn main() {
+/// let timer = MockTimer::new();
+/// let deadline = timer.now() + Duration::from_secs(5);
+/// timer.advance(Duration::from_secs(5));
+///
+/// let output = selectme::select! {
+///     timer = &timer;
+///
+///     () = std::future::pending::<()>() => "completed",
+///     after(Duration::from_secs(10)) => "slept",
+///     at(deadline) => "deadline reached",
+/// };
+///
+/// assert_eq!(output, "deadline reached");
+/// # }
+/// ```
+///
+/// [`TokioTimer`]: https://docs.rs/selectme/latest/selectme/struct.TokioTimer.html
+/// [`tokio::time::Instant`]: https://docs.rs/tokio/latest/tokio/time/struct.Instant.html
+/// [`MockTimer`]: https://docs.rs/selectme/latest/selectme/struct.MockTimer.html
+///
+/// # Branch timeouts
+///
+/// A branch can be given a timeout through the `timeout(<duration>)` modifier,
+/// which goes after the `<async expression>` and before any `if` precondition.
+/// If the branch hasn't completed once the duration has elapsed, it is disabled
+/// in the same way as a branch whose pattern doesn't match and the remaining
+/// branches keep running.
+///
+/// A branch with a timeout may be directly followed by a `timeout` arm.
+/// Instead of disabling the branch, timing out then completes the select by
+/// evaluating the arm like any other handler:
+///
+/// ```text
+/// <pattern> = <async expression>, timeout(<duration>) (if <precondition>)? => <handler>,
+/// timeout => <on timeout>,
+/// ```
+///
+/// The timeout is constructed through the same [Timer][crate::Timer] as timer
+/// branches.
+///
+/// ```
+/// use std::future::pending;
+/// use std::time::Duration;
+///
+/// use selectme::MockTimer;
+///
+/// # #[selectme::main] async fn main() {
+/// let timer = MockTimer::new();
+///
+/// let output = selectme::select! {
+///     timer = &timer;
+///
+///     v = pending::<u32>(), timeout(Duration::ZERO) => Some(v),
+///     timeout => None,
+/// };
+///
+/// assert_eq!(output, None);
+/// # }
+/// ```
+///
+/// # Delayed branches
+///
+/// A branch can be started with a delay through the `start_after(<duration>)`
+/// modifier. Its `<async expression>` is not evaluated until the duration has
+/// elapsed, which is useful for hedged requests where a backup should only be
+/// issued if the primary request is taking too long.
+///
+/// ```text
+/// <pattern> = <async expression>, start_after(<duration>) (if <precondition>)? => <handler>,
+/// ```
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use selectme::MockTimer;
+///
+/// async fn primary() -> u32 {
+///     1
+/// }
+///
+/// async fn backup() -> u32 {
+///     unreachable!("backup is never started")
+/// }
+///
+/// # #[selectme::main] async fn main() {
+/// let timer = MockTimer::new();
+///
+/// let output = selectme::select! {
+///     timer = &timer;
+///
+///     v = primary() => v,
+///     v = backup(), start_after(Duration::from_millis(50)) => v,
+/// };
+///
+/// assert_eq!(output, 1);
+/// # }
+/// ```
+///
+/// # Retried branches
+///
+/// Normally a branch whose pattern doesn't match is disabled. With the
+/// `retry(<n>)` modifier the `<async expression>` is instead evaluated again
+/// to rebuild the future in place up to `<n>` times before the branch is
+/// disabled. With the `repeat` modifier it is rebuilt indefinitely.
+///
+/// ```text
+/// <pattern> = <async expression>, retry(<n>) (if <precondition>)? => <handler>,
+/// <pattern> = <async expression>, repeat (if <precondition>)? => <handler>,
+/// ```
+///
+/// Since the `<async expression>` is evaluated multiple times, it is wrapped
+/// in an [`FnMut`] closure. So the future it produces can't hold on to any
+/// exclusive borrows it captures. If a retried branch in
+/// [inline!][crate::inline!] also uses the timer, like through
+/// `start_after(<duration>)`, the closure keeps its own clone of the timer,
+/// which therefore has to implement [`Clone`].
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// # #[selectme::main] async fn main() {
+/// let attempts = Cell::new(0);
+///
+/// let attempt = || async {
+///     attempts.set(attempts.get() + 1);
+///     attempts.get() >= 3
+/// };
+///
+/// let output = selectme::select! {
+///     true = attempt(), retry(5) => Some(attempts.get()),
+///     else => None,
+/// };
+///
+/// assert_eq!(output, Some(3));
+/// # }
+/// ```
+///
+/// # Batched branches
+///
+/// A branch which produces many values, like a stream implementing
+/// [SelectBranch][crate::SelectBranch], can be drained in batches through the
+/// `batch(<n>)` modifier, where `<n>` is a `usize` expression greater than
+/// zero. A literal `0` is rejected at compile time, while any other limit of
+/// zero panics when the select is constructed. Once the branch is ready, it keeps being polled until it has produced
+/// `<n>` items, is pending, or is finished. The items are then handed to the
+/// handler as a single [`Vec`], so the handler only runs once per batch while
+/// fairness between branches is still maintained by the bias of the select.
+/// This requires the `alloc` feature.
+///
+/// A [`Stream`] can be used in such a branch by wrapping it in a
+/// [`StreamBranch`], which requires the `futures` feature.
+///
+/// ```text
+/// <pattern> = <async expression>, batch(<n>) (if <precondition>)? => <handler>,
+/// ```
+///
+/// ```
+/// use std::pin::Pin;
+/// use std::task::{Context, Poll};
+///
+/// use selectme::SelectBranch;
+///
+/// /// A branch which produces the given items.
+/// struct Items(Vec<u32>);
+///
+/// impl SelectBranch for Items {
+///     type Output = u32;
+///
+///     fn poll_branch(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+///         Poll::Ready(self.0.remove(0))
+///     }
+///
+///     fn is_finished(&self) -> bool {
+///         self.0.is_empty()
+///     }
+/// }
+///
+/// # #[selectme::main] async fn main() {
+/// let output = selectme::select! {
+///     items = Items(vec![1, 2, 3, 4, 5]), batch(3) => items,
+/// };
+///
+/// assert_eq!(output, [1, 2, 3]);
+/// # }
+/// ```
+///
+/// # Send branches
+///
+/// A branch can also send a value into a channel, which completes once the
+/// channel has capacity for it:
+///
+/// ```text
+/// send(<sender>, <value>) (if <precondition>)? => <handler>,
+/// ```
+///
+/// The branch reserves a permit through `<sender>.reserve()`, which is either
+/// the method of [SelectSend][crate::SelectSend] or the `reserve` method of a
+/// Tokio `mpsc::Sender` if the `tokio` feature is enabled. `<value>` is only
+/// evaluated and sent once the branch has won. If another branch completes
+/// first the value is left untouched, and if the channel is closed the branch
+/// is disabled.
+///
+/// Since the handlers of [inline!][crate::inline!] can't move out of their
+/// environment, it instead evaluates `<value>` when the select is constructed
+/// and moves it into the branch. A value which hasn't been sent can be taken
+/// back through [Sending][crate::Sending].
+///
+/// ```
+/// use std::collections::VecDeque;
+///
+/// use tokio::sync::mpsc;
+///
+/// # #[cfg(not(feature = "tokio"))] fn main() {}
+/// # #[cfg(feature = "tokio")]
+/// # #[selectme::main] async fn main() {
+/// let (tx, mut rx) = mpsc::channel(1);
+/// let mut queue = VecDeque::from([1, 2, 3]);
+/// let mut received = Vec::new();
+///
+/// while received.len() < 3 {
+///     selectme::select! {
+///         send(&tx, queue.pop_front().unwrap()), if !queue.is_empty() => {}
+///         Some(value) = rx.recv() => received.push(value),
+///     }
+/// }
+///
+/// assert_eq!(received, [1, 2, 3]);
+/// # }
+/// ```
+///
+/// # Conditionally compiled branches
+///
+/// Branches can be preceded by `#[cfg(<predicate>)]` attributes, in which case
+/// they only exist if the predicate holds. A branch which is compiled out is
+/// treated as permanently disabled, and neither its expression, its
+/// precondition nor its handler are compiled. It still keeps its index, so the
+/// indexes of the remaining branches don't depend on which features are
+/// enabled.
+///
+/// Multiple attributes on the same branch must all hold, and no other
+/// attributes are supported.
+///
+/// ```
+/// use std::future::ready;
+///
+/// # #[selectme::main] async fn main() {
+/// let output = selectme::select! {
+///     biased;
+///
+///     #[cfg(debug_assertions)]
+///     value = ready("debug") => value,
+///     value = ready("release") => value,
+/// };
+///
+/// assert_eq!(output, if cfg!(debug_assertions) { "debug" } else { "release" });
+/// # }
+/// ```
+///
+/// # Cancelling a select
+///
+/// The `cancel = <future>;` option adds an implicit branch which completes
+/// once the given future has completed, in which case the `cancelled` arm is
+/// evaluated:
+///
+/// ```text
+/// cancelled => <handler>,
+/// ```
+///
+/// This is useful for long-running selects which should stop on something
+/// like a shutdown signal. The cancellation branch is always polled before any
+/// other branch regardless of the bias of the select, so a select which has
+/// been cancelled never evaluates another branch. It's declared after all
+/// other branches, so its index is the number of branches in the select.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use tokio::time;
+///
+/// # #[selectme::main] async fn main() {
+/// let shutdown = time::sleep(Duration::from_millis(50));
+/// tokio::pin!(shutdown);
+///
+/// loop {
+///     selectme::select! {
+///         cancel = &mut shutdown;
+///
+///         _ = time::sleep(Duration::from_millis(10)) => {
+///             // Perform periodic work.
+///         }
+///         cancelled => break,
+///     }
+/// }
+///
+/// assert!(shutdown.is_elapsed());
+/// # }
+/// ```
+///
+/// # Boxed state
+///
+/// The futures of all branches are normally stored inline in the future
+/// produced by `select!`, which for selects with many large branches can make
+/// the enclosing future very large. The `boxed;` option instead stores them in
+/// a single heap allocation, which requires the `alloc` feature. Each branch is
+/// evaluated and then written into the allocation one at a time, so at most one
+/// branch rather than the state as a whole is held on the stack.
+///
+/// ```
+/// use std::future::ready;
+///
+/// # #[selectme::main] async fn main() {
+/// let large = async {
+///     let buf = [0u8; 1024];
+///     ready(()).await;
+///     buf.len()
+/// };
+///
+/// let output = selectme::select! {
+///     boxed;
+///
+///     len = large => len,
+/// };
+///
+/// assert_eq!(output, 1024);
+/// # }
+/// ```
+///
+/// # Catching panics
+///
+/// A panic in a branch future normally unwinds through the select. With the
+/// `catch_unwind;` option, which requires the `std` feature, every branch is
+/// instead polled through [`catch_unwind`]. A branch which panics is disabled
+/// and the select completes through the `panicked` arm, which binds the index
+/// of the branch and the panic payload:
+///
+/// ```text
+/// panicked(<index>, <payload>) => <handler>,
+/// ```
+///
+/// ```
+/// # #[selectme::main] async fn main() {
+/// async fn boom() -> u32 {
+///     panic!("boom");
+/// }
+///
+/// let output = selectme::select! {
+///     catch_unwind;
+///
+///     value = boom() => value,
+///     panicked(index, payload) => {
+///         assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+///         index
+///     }
+/// };
+///
+/// assert_eq!(output, 0);
+/// # }
+/// ```
+///
+/// [`catch_unwind`]: std::panic::catch_unwind
+///
+/// # Quorums
+///
+/// With the `quorum = <n>;` option, which requires the `alloc` feature, the
+/// select keeps polling its branches until `<n>` of them have completed
+/// instead of stopping at the first one. The remaining branches are then
+/// cancelled, and the select evaluates to a [`Vec`] of the outputs of their
+/// handlers tagged with the index of their branch, in the order in which the
+/// branches completed. A branch which produces multiple outputs only counts
+/// once towards the quorum, and neither does the `timeout` arm of a branch,
+/// but their outputs are still collected.
+///
+/// The `else` arm is evaluated as soon as the quorum can no longer be reached
+/// because too many branches have been disabled, such as through a pattern
+/// which didn't match. So it has to evaluate to the same type, or use control
+/// flow like `return`. Without an `else` arm this causes a panic.
+///
+/// Handlers are evaluated one after another once the quorum has been reached,
+/// so like any other handler they can use `.await`, `return` and `?`. A
+/// `break` or `continue` targeting a loop around the select stops evaluating
+/// handlers, and takes effect once the remaining outputs have been dropped.
+///
+/// ```
+/// use std::future::{pending, ready};
+///
+/// async fn write(replica: &str) -> Result<&str, ()> {
+///     match replica {
+///         "slow" => pending().await,
+///         replica => ready(Ok(replica)).await,
+///     }
+/// }
+///
+/// # #[selectme::main] async fn main() {
+/// let acks = selectme::select! {
+///     quorum = 2;
+///
+///     Ok(ack) = write("first") => ack,
+///     Ok(ack) = write("slow") => ack,
+///     Ok(ack) = write("third") => ack,
+///     else => Vec::new(),
+/// };
+///
+/// assert_eq!(acks.len(), 2);
+/// assert!(acks.contains(&(0, "first")));
+/// assert!(acks.contains(&(2, "third")));
+/// # }
+/// ```
+///
+/// # Migrating from `futures`
+///
+/// The `compat(futures);` option, which requires the `futures` feature, makes
+/// `select!` accept the syntax of `futures::select!`, in which case the
+/// `complete` and `default` arms are supported:
+///
+/// ```text
+/// complete => <expression>,
+/// default => <expression>,
+/// ```
+///
+/// The `complete` arm is evaluated once all branches have been disabled, so
+/// it's the same as the `else` branch. The `default` arm is evaluated if no
+/// branch is ready when the select is first polled, which makes the select
+/// non-blocking.
+///
+/// Just like with `futures::select!` the future of each branch has to
+/// implement [`FusedFuture`], and a branch whose future has terminated is
+/// disabled. A future which is a local variable is borrowed mutably, so that
+/// it can be used again by the next select in a loop.
+///
+/// The [futures_select!][crate::futures_select!] and
+/// [futures_select_biased!][crate::futures_select_biased!] macros apply this
+/// option, so they can be imported in place of the macros from `futures`.
+///
+/// ```
+/// use std::future::{pending, ready};
+///
+/// # use std::future::Future;
+/// # use std::pin::Pin;
+/// # use std::task::{Context, Poll};
+/// # struct Fuse<F>(Option<F>);
+/// # fn fuse<F>(future: F) -> Fuse<F> { Fuse(Some(future)) }
+/// # impl<F: Future + Unpin> Future for Fuse<F> {
+/// #     type Output = F::Output;
+/// #     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+/// #         let output = match &mut self.0 {
+/// #             Some(future) => match Pin::new(future).poll(cx) {
+/// #                 Poll::Ready(output) => output,
+/// #                 Poll::Pending => return Poll::Pending,
+/// #             },
+/// #             None => return Poll::Pending,
+/// #         };
+/// #         self.0 = None;
+/// #         Poll::Ready(output)
+/// #     }
+/// # }
+/// # impl<F: Future + Unpin> futures_core::FusedFuture for Fuse<F> {
+/// #     fn is_terminated(&self) -> bool { self.0.is_none() }
+/// # }
+/// # #[cfg(not(feature = "futures"))] fn main() {}
+/// # #[cfg(feature = "futures")]
+/// # #[selectme::main] async fn main() {
+/// let mut a = fuse(ready(1));
+/// let mut b = fuse(ready(2));
+/// let mut values = Vec::new();
+///
+/// loop {
+///     selectme::select! {
+///         compat(futures);
+///
+///         value = a => values.push(value),
+///         value = b => values.push(value),
+///         complete => break,
+///     }
+/// }
+///
+/// values.sort();
+/// assert_eq!(values, [1, 2]);
+///
+/// let output = selectme::select! {
+///     compat(futures);
+///
+///     value = fuse(pending::<u32>()) => value,
+///     default => 0,
+/// };
+///
+/// assert_eq!(output, 0);
+/// # }
+/// ```
+///
+/// [`FusedFuture`]: https://docs.rs/futures/latest/futures/future/trait.FusedFuture.html
+///
+/// # Send assertions
+///
+/// If the future produced by `select!` has to be `Send`, such as when it's
+/// passed to `tokio::spawn`, the error produced when it isn't can be hard to
+/// track down. The `send;` option asserts that the future of each branch is
+/// `Send`, and reports any error on the offending branch expression.
+///
+/// ```
+/// use std::future::ready;
+///
+/// # #[selectme::main] async fn main() {
+/// let task = tokio::spawn(async {
+///     selectme::select! {
+///         send;
+///
+///         value = ready(1) => value,
+///     }
+/// });
+///
+/// assert_eq!(task.await.unwrap(), 1);
+/// # }
+/// ```
 ///
 /// # Runtime characteristics
 ///
@@ -66,6 +620,47 @@
 /// describes whether they are cancel safe.  The lists in this section are not
 /// exhaustive.
 ///
+/// The `cancel_safe;` option checks this at compile time, by requiring that
+/// the future of every branch implements [CancelSafe][crate::CancelSafe].
+/// Futures which are borrowed, such as `&mut sleep`, are always cancellation
+/// safe since they outlive the select. Receiving from a Tokio channel can be
+/// done through [Recv] and [UnboundedRecv] if the `tokio` feature is enabled.
+/// Send branches reserve their permit through [SelectSend] with this option,
+/// so the future returned by [SelectSend::reserve] has to be cancellation safe
+/// as well.
+///
+/// ```
+/// use std::future::ready;
+/// use std::time::Duration;
+///
+/// use tokio::time;
+///
+/// # #[selectme::main] async fn main() {
+/// let sleep = time::sleep(Duration::from_millis(10));
+/// tokio::pin!(sleep);
+///
+/// let mut n = 0;
+///
+/// loop {
+///     selectme::select! {
+///         cancel_safe;
+///
+///         () = &mut sleep => break,
+///         () = ready(()), if n < 3 => n += 1,
+///     }
+/// }
+///
+/// assert_eq!(n, 3);
+/// # }
+/// ```
+///
+/// [Recv]: https://docs.rs/selectme/latest/selectme/struct.Recv.html
+/// [UnboundedRecv]: https://docs.rs/selectme/latest/selectme/struct.UnboundedRecv.html
+/// [SelectSend]: https://docs.rs/selectme/latest/selectme/trait.SelectSend.html
+/// [SelectSend::reserve]: https://docs.rs/selectme/latest/selectme/trait.SelectSend.html#tymethod.reserve
+/// [`Stream`]: https://docs.rs/futures/latest/futures/stream/trait.Stream.html
+/// [`StreamBranch`]: https://docs.rs/selectme/latest/selectme/struct.StreamBranch.html
+///
 /// # Examples
 ///
 /// Basic select with two branches.
@@ -387,6 +982,11 @@ macro_rules! select {
 /// # }
 /// ```
 ///
+/// The state of the select holds one element per branch, which can be
+/// accessed by the index of the branch through [StateField][crate::StateField].
+/// Since it is only implemented for up to 16 branches, an `inline!` select is
+/// limited to 16 branches as well, unlike [select!] which supports up to 128.
+///
 /// <br>
 ///
 /// # Static selects
@@ -395,6 +995,15 @@ macro_rules! select {
 /// the select that is generated to be named. This can be useful if you want to
 /// incorporate selection-like behaviour into another future.
 ///
+/// The branches of a static select are polled through a zero-sized poller
+/// implementing [Poller][crate::Poller], which is statically dispatched just
+/// like the poller of a regular [Select][crate::Select]. When the select is
+/// named through the type of its output as below, the poller is coerced into a
+/// [PollFn][crate::PollFn] at the cost of an indirect call. Since the poller
+/// can't capture its environment, a function constructing the select can
+/// instead return it as `StaticSelect<.., impl Poller<..>>` which keeps the
+/// poller statically dispatched.
+///
 /// Note that this option is not supported in [select!].
 ///
 /// ```
@@ -440,6 +1049,12 @@ macro_rules! select {
 /// # }
 /// ```
 ///
+/// Since the handlers of a static select can't capture their environment, the
+/// `context: <type>;` option can be used to have them receive a `&mut <type>`
+/// named `context` instead. The context is provided when polling through
+/// [StaticSelect::poll_next_with][crate::StaticSelect::poll_next_with], which
+/// allows handlers to update the fields of the future embedding the select.
+///
 /// <br>
 ///
 /// # Examples
@@ -475,3 +1090,167 @@ macro_rules! inline {
         $crate::__support::inline!($crate, $($tt)*)
     }};
 }
+
+/// Define a `select!` macro with a set of options which are always applied.
+///
+/// The first argument is the name of the macro to define, and it's followed by
+/// the options to apply. This makes it possible to use options like `biased;`
+/// or `cancel = <future>;` for every select in a project without repeating
+/// them. Additional options can be provided at each use of the defined macro.
+///
+/// The defined macro is a regular `macro_rules!` macro, so it's only in scope
+/// after its definition unless it's re-exported with something like `pub(crate)
+/// use <name>;`. Due to macro hygiene, the preset options can refer to items
+/// like statics but not to local variables at the site where the macro is
+/// used.
+///
+/// # Examples
+///
+/// ```
+/// use std::future::ready;
+///
+/// selectme::define_select!(biased_select, biased;);
+///
+/// # #[selectme::main] async fn main() {
+/// let output = biased_select! {
+///     value = ready(1) => value,
+///     value = ready(2) => value,
+/// };
+///
+/// assert_eq!(output, 1);
+/// # }
+/// ```
+///
+/// The `cancel = <future>;` option requires a `cancelled =>` arm. A default
+/// arm can be provided after the options, which is used by every select that
+/// doesn't specify its own:
+///
+/// ```
+/// use std::future::{pending, ready};
+///
+/// async fn shutdown() {
+///     pending().await
+/// }
+///
+/// selectme::define_select!(service_select, biased; cancel = shutdown(); cancelled => None);
+///
+/// # #[selectme::main] async fn main() {
+/// let output = service_select! {
+///     value = ready(1) => Some(value),
+/// };
+///
+/// assert_eq!(output, Some(1));
+///
+/// let output = service_select! {
+///     value = pending() => Some(value),
+///     value = ready(2) => Some(value),
+///     cancelled => Some(0),
+/// };
+///
+/// assert_eq!(output, Some(2));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_select {
+    ($name:ident, $($options:tt)*) => {
+        $crate::define_select!(@parse ($) $name, [] $($options)*);
+    };
+
+    // The default `cancelled =>` arm is passed on as a braced group.
+    (@parse ($d:tt) $name:ident, [$($options:tt)*] cancelled => $cancelled:expr $(,)?) => {
+        $crate::define_select!(@define ($d) $name, { $cancelled } $($options)*);
+    };
+
+    (@parse ($d:tt) $name:ident, [$($options:tt)*] $head:tt $($tail:tt)*) => {
+        $crate::define_select!(@parse ($d) $name, [$($options)* $head] $($tail)*);
+    };
+
+    (@parse ($d:tt) $name:ident, [$($options:tt)*]) => {
+        $crate::define_select!(@define ($d) $name, $($options)*);
+    };
+
+    (@define ($d:tt) $name:ident, $($options:tt)*) => {
+        macro_rules! $name {
+            ($d($d tt:tt)*) => {{
+                $crate::__support::select!($crate, $($options)* $d($d tt)*)
+            }};
+        }
+    };
+}
+
+/// A `select!` which accepts the syntax of `futures::select!`, which makes it
+/// possible to migrate by swapping imports.
+///
+/// This is the same as [select!][crate::select!] with the `compat(futures);`
+/// option, which requires the `futures` feature. See [Migrating from
+/// `futures`][crate::select!#migrating-from-futures].
+///
+/// # Examples
+///
+/// ```
+/// use std::future::{pending, ready};
+///
+/// use selectme::futures_select as select;
+///
+/// # use std::future::Future;
+/// # use std::pin::Pin;
+/// # use std::task::{Context, Poll};
+/// # struct Fuse<F>(Option<F>);
+/// # fn fuse<F>(future: F) -> Fuse<F> { Fuse(Some(future)) }
+/// # impl<F: Future + Unpin> Future for Fuse<F> {
+/// #     type Output = F::Output;
+/// #     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+/// #         let output = match &mut self.0 {
+/// #             Some(future) => match Pin::new(future).poll(cx) {
+/// #                 Poll::Ready(output) => output,
+/// #                 Poll::Pending => return Poll::Pending,
+/// #             },
+/// #             None => return Poll::Pending,
+/// #         };
+/// #         self.0 = None;
+/// #         Poll::Ready(output)
+/// #     }
+/// # }
+/// # impl<F: Future + Unpin> futures_core::FusedFuture for Fuse<F> {
+/// #     fn is_terminated(&self) -> bool { self.0.is_none() }
+/// # }
+/// # #[cfg(not(feature = "futures"))] fn main() {}
+/// # #[cfg(feature = "futures")]
+/// # #[selectme::main] async fn main() {
+/// let mut a = fuse(ready(1));
+/// let mut b = fuse(pending::<u32>());
+///
+/// let output = select! {
+///     value = a => Some(value),
+///     value = b => Some(value),
+///     default => None,
+/// };
+///
+/// assert_eq!(output, Some(1));
+///
+/// let output = select! {
+///     value = a => Some(value),
+///     value = b => Some(value),
+///     default => None,
+/// };
+///
+/// assert_eq!(output, None);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! futures_select {
+    ($($tt:tt)*) => {{
+        $crate::__support::select!($crate, compat(futures); $($tt)*)
+    }};
+}
+
+/// A biased `select!` which accepts the syntax of `futures::select_biased!`.
+///
+/// This is the same as [select!][crate::select!] with the `compat(futures);`
+/// and `biased;` options, see [futures_select!][crate::futures_select!].
+#[macro_export]
+macro_rules! futures_select_biased {
+    ($($tt:tt)*) => {{
+        $crate::__support::select!($crate, compat(futures); biased; $($tt)*)
+    }};
+}
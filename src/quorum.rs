@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::bias::Bias;
+use crate::select::Select;
+use crate::set::{Number, Set};
+use crate::static_select::{Poller, StaticSelect};
+
+/// A selection which can be polled until a quorum of its branches have
+/// completed.
+pub trait QuorumSelect {
+    /// The output of a single branch.
+    type Output;
+
+    /// Get the number of branches which are still enabled.
+    fn remaining(&self) -> u32;
+
+    /// Poll for the next branch to resolve, or [`None`] once all branches
+    /// have been disabled.
+    fn poll_try_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Output>>;
+}
+
+impl<Bits, S, B, T, O> QuorumSelect for Select<Bits, S, B, T>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    T: FnMut(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
+{
+    type Output = O;
+
+    #[inline]
+    fn remaining(&self) -> u32 {
+        Select::remaining(self)
+    }
+
+    #[inline]
+    fn poll_try_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        Select::poll_try_next(self, cx)
+    }
+}
+
+impl<Bits, S, B, O, P> QuorumSelect for StaticSelect<Bits, S, B, O, P>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
+{
+    type Output = O;
+
+    #[inline]
+    fn remaining(&self) -> u32 {
+        StaticSelect::remaining(self)
+    }
+
+    #[inline]
+    fn poll_try_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        StaticSelect::poll_try_next(self, cx)
+    }
+}
+
+/// A future which polls a selection until `quorum` of its branches have
+/// completed. This is used by the `quorum = <n>;` option.
+///
+/// The `branch` function identifies which branch an output belongs to, or
+/// [`None`] if it doesn't count as a branch completing, like the output of a
+/// `timeout` arm. Every branch only counts towards the quorum once, even if it
+/// produces multiple outputs.
+///
+/// It resolves to the collected outputs in the order in which they
+/// completed, or to [`None`] as soon as there aren't enough branches left to
+/// reach the quorum.
+pub struct Quorum<T, F, O> {
+    select: T,
+    branch: F,
+    quorum: usize,
+    completed: u128,
+    outputs: Vec<O>,
+}
+
+impl<T, F, O> Quorum<T, F, O> {
+    pub(crate) fn new(quorum: usize, select: T, branch: F) -> Self {
+        Self {
+            select,
+            branch,
+            quorum,
+            completed: 0,
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl<T, F> Future for Quorum<T, F, T::Output>
+where
+    T: QuorumSelect,
+    F: Fn(&T::Output) -> Option<u32>,
+{
+    type Output = Option<Vec<T::Output>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: Quorum is safely pinned. The select is structurally pinned,
+        // while the rest of the state is not.
+        let (mut select, branch, quorum, completed, outputs) = unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            (
+                Pin::new_unchecked(&mut this.select),
+                &this.branch,
+                this.quorum,
+                &mut this.completed,
+                &mut this.outputs,
+            )
+        };
+
+        loop {
+            let count = completed.count_ones() as usize;
+
+            if count >= quorum {
+                return Poll::Ready(Some(mem::take(outputs)));
+            }
+
+            if count + (select.remaining() as usize) < quorum {
+                return Poll::Ready(None);
+            }
+
+            match select.as_mut().poll_try_next(cx) {
+                Poll::Ready(Some(output)) => {
+                    if let Some(index) = branch(&output) {
+                        *completed |= 1 << index;
+                    }
+
+                    outputs.push(output);
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Control flow used by the handler of a select with the `quorum` option,
+/// which is evaluated in the enclosing scope once every output has been
+/// handled.
+#[derive(Debug)]
+pub enum Control<B> {
+    /// No control flow was used.
+    Proceed,
+    /// A `break` without a value.
+    Break,
+    /// A `break` with a value.
+    BreakWith(B),
+    /// A `continue`.
+    Continue,
+}
+
+impl<T, F, O> fmt::Debug for Quorum<T, F, O>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Quorum")
+            .field("select", &self.select)
+            .field("quorum", &self.quorum)
+            .field("completed", &self.completed.count_ones())
+            .finish()
+    }
+}
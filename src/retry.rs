@@ -0,0 +1,89 @@
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::branch::SelectBranch;
+
+/// A branch future which can be rebuilt through its factory. This is used by
+/// branches with the `retry(<n>)` or `repeat` modifiers.
+///
+/// The factory is passed a reference to the given context, which holds the
+/// timer if the future is constructed through one.
+pub struct Retry<C, F, T> {
+    context: C,
+    factory: F,
+    future: T,
+    remaining: Option<usize>,
+}
+
+impl<C, F, T> Retry<C, F, T>
+where
+    F: FnMut(&C) -> T,
+{
+    pub(crate) fn new(remaining: Option<usize>, context: C, mut factory: F) -> Self {
+        let future = factory(&context);
+
+        Self {
+            context,
+            factory,
+            future,
+            remaining,
+        }
+    }
+
+    /// Rebuild the future in place if there are any remaining attempts and
+    /// return a boolean indicating if it was rebuilt.
+    fn retry(self: Pin<&mut Self>) -> bool {
+        // SAFETY: Retry is safely pinned. The future is structurally pinned,
+        // while the factory is not.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+
+            match &mut this.remaining {
+                Some(0) => return false,
+                Some(remaining) => *remaining -= 1,
+                None => {}
+            }
+
+            // NB: This drops the previous future in place.
+            this.future = (this.factory)(&this.context);
+            true
+        }
+    }
+}
+
+impl<C, F, T> SelectBranch for Retry<C, F, T>
+where
+    F: FnMut(&C) -> T,
+    T: SelectBranch,
+{
+    type Output = T::Output;
+
+    #[inline]
+    fn poll_branch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: Retry is safely pinned.
+        unsafe { Pin::map_unchecked_mut(self, |this| &mut this.future).poll_branch(cx) }
+    }
+
+    #[inline]
+    fn is_finished(&self) -> bool {
+        self.future.is_finished()
+    }
+
+    #[inline]
+    fn rearm(self: Pin<&mut Self>) -> bool {
+        self.retry()
+    }
+}
+
+impl<C, F, T> fmt::Debug for Retry<C, F, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("future", &self.future)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
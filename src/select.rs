@@ -1,10 +1,15 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::fmt;
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
 use crate::bias::Bias;
+use crate::branch::SelectBranch;
 use crate::set::{Number, Set};
+use crate::slot::{Slot, State};
+use crate::state::StateField;
 
 /// Index which indicates that all branches have been disabled.
 pub const DISABLED: u32 = u32::MAX;
@@ -53,13 +58,13 @@ pub const DISABLED: u32 = u32::MAX;
 /// ```
 pub struct Select<Bits, S, B, T> {
     enabled: Set<Bits>,
-    state: S,
+    state: State<Bits, S>,
     bias: B,
     poll: T,
 }
 
 impl<Bits, S, B, T> Select<Bits, S, B, T> {
-    pub(crate) fn new(enabled: Set<Bits>, bias: B, state: S, poll: T) -> Self {
+    pub(crate) fn new(enabled: Set<Bits>, bias: B, state: State<Bits, S>, poll: T) -> Self {
         Self {
             enabled,
             state,
@@ -69,6 +74,162 @@ impl<Bits, S, B, T> Select<Bits, S, B, T> {
     }
 }
 
+impl<Bits, S, B, T> Select<Bits, S, B, T>
+where
+    Bits: Number,
+{
+    /// Test if all branches have been disabled.
+    ///
+    /// Once this returns `true`, [Select::try_next] will resolve to
+    /// [`None`].
+    pub fn is_terminated(&self) -> bool {
+        self.enabled.is_empty()
+    }
+
+    /// Get the number of branches which are still enabled.
+    pub fn remaining(&self) -> u32 {
+        self.enabled.len()
+    }
+
+    /// Disable the branch with the given `index`, causing it to no longer be
+    /// polled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the branch mask.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     biased;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     value = ready(2) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().disable(0);
+    /// assert_eq!(output.as_mut().next().await, Some(2));
+    /// assert_eq!(output.as_mut().next().await, None);
+    /// # }
+    /// ```
+    pub fn disable(self: Pin<&mut Self>, index: u32) {
+        assert!(index < Bits::BITS, "branch index out of bounds");
+        // SAFETY: The mask is not structurally pinned.
+        unsafe { Pin::get_unchecked_mut(self).enabled.clear(index) }
+    }
+
+    /// Enable the branch with the given `index`, causing it to be polled again.
+    ///
+    /// Care must be taken to only enable branches which can be polled. A
+    /// branch which has already completed will typically panic if it is polled
+    /// again unless it has been replaced through
+    /// [Select::set_branch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the branch mask, or if it's a
+    /// conditional branch which is empty.
+    pub fn enable(self: Pin<&mut Self>, index: u32) {
+        assert!(index < Bits::BITS, "branch index out of bounds");
+
+        // SAFETY: The mask is not structurally pinned.
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+
+        assert!(
+            this.state.initialized().contains(index),
+            "branch `{index}` is empty"
+        );
+
+        this.enabled.set(index);
+    }
+
+    /// Access the pinned branch with index `N`, which is usually a future.
+    ///
+    /// Note that this does not enable or disable the branch. Conditional
+    /// branches can't be accessed like this, instead use
+    /// [Select::set_branch] to replace their future.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     value = ready(1) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().branch::<0>().set(ready(42));
+    /// assert_eq!(output.as_mut().next().await, Some(42));
+    /// # }
+    /// ```
+    pub fn branch<const N: usize>(self: Pin<&mut Self>) -> Pin<&mut S::Output>
+    where
+        S: StateField<N>,
+        S::Output: SelectBranch,
+    {
+        // SAFETY: The state is structurally pinned.
+        unsafe {
+            let state = Pin::new_unchecked(&mut Pin::get_unchecked_mut(self).state);
+            StateField::project(State::project(state))
+        }
+    }
+
+    /// Replace the future of the branch with index `N` and enable it.
+    ///
+    /// This is only supported for branches which are stored in a [Slot], which
+    /// is the case for branches which have an `if` precondition. A
+    /// precondition of `if false` can be used to declare a branch which
+    /// starts out empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     value = ready(0), if false => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().set_branch::<0, _>(ready(42));
+    /// assert_eq!(output.as_mut().next().await, Some(42));
+    /// assert_eq!(output.as_mut().next().await, None);
+    /// # }
+    /// ```
+    pub fn set_branch<const N: usize, F>(self: Pin<&mut Self>, future: F)
+    where
+        S: StateField<N, Output = Slot<F>>,
+    {
+        // SAFETY: The state is structurally pinned, the mask is not.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            Pin::new_unchecked(&mut this.state).set_slot::<N, F>(future);
+            this.enabled.set(N as u32);
+        }
+    }
+
+    /// Replace the bias used when selecting the order in which branches are
+    /// polled.
+    pub fn set_bias(self: Pin<&mut Self>, bias: B) {
+        // SAFETY: The bias is not structurally pinned.
+        unsafe { Pin::get_unchecked_mut(self).bias = bias }
+    }
+}
+
 impl<Bits, S, B, T, O> Select<Bits, S, B, T>
 where
     Bits: Number,
@@ -110,26 +271,142 @@ where
         Next { this: self }.await
     }
 
+    /// Get the next element from this select when pinned, or [`None`] if all
+    /// branches have been disabled.
+    ///
+    /// Unlike [Select::next] this does not evaluate the `else` branch once
+    /// all branches have been disabled, which makes it possible to drain a
+    /// selection which doesn't have one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     value = ready(1) => value,
+    ///     value = ready(2) => value,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// let mut values = Vec::new();
+    ///
+    /// while let Some(value) = output.as_mut().try_next().await {
+    ///     values.push(value);
+    /// }
+    ///
+    /// values.sort();
+    /// assert_eq!(values, &[1, 2]);
+    /// assert!(output.is_terminated());
+    /// # }
+    /// ```
+    pub async fn try_next(self: Pin<&mut Self>) -> Option<O> {
+        TryNext { this: self }.await
+    }
+
+    /// Get every output which is ready in a single pass over the branches of
+    /// this select when pinned.
+    ///
+    /// Unlike [Select::next] this doesn't stop at the first branch which is
+    /// ready. Every enabled branch is polled once, and the outputs of the ones
+    /// which completed are returned in the order they were polled in, as
+    /// determined by the bias. Once all branches have been disabled the batch
+    /// consists of the output of the `else` branch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     biased;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     value = ready(2) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// assert_eq!(output.as_mut().next_batch().await, [Some(1), Some(2)]);
+    /// assert_eq!(output.as_mut().next_batch().await, [None]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub async fn next_batch(self: Pin<&mut Self>) -> Vec<O> {
+        NextBatch { this: self }.await
+    }
+
+    /// Poll for every branch which is ready in a single pass over this
+    /// [Select], see [Select::next_batch].
+    #[cfg(feature = "alloc")]
+    pub fn poll_ready_batch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Vec<O>> {
+        // SAFETY: Select is safely pinned.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            let mut state = Pin::new_unchecked(&mut this.state).project();
+            let mut outputs = Vec::new();
+
+            for index in this.bias.apply(this.enabled) {
+                if let Poll::Ready(output) =
+                    (this.poll)(cx, state.as_mut(), &mut this.enabled, index)
+                {
+                    outputs.push(output);
+                }
+            }
+
+            if !outputs.is_empty() {
+                return Poll::Ready(outputs);
+            }
+
+            if this.enabled.is_empty() {
+                if let Poll::Ready(output) = (this.poll)(cx, state, &mut this.enabled, DISABLED) {
+                    outputs.push(output);
+                    return Poll::Ready(outputs);
+                }
+            }
+
+            Poll::Pending
+        }
+    }
+
     /// Poll for the next branch to resolve in this [Select].
-    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<O> {
+    pub fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<O> {
+        match self.as_mut().poll_try_next(cx) {
+            Poll::Ready(Some(output)) => Poll::Ready(output),
+            // SAFETY: Select is safely pinned.
+            Poll::Ready(None) => unsafe {
+                let this = Pin::get_unchecked_mut(self);
+                let state = Pin::new_unchecked(&mut this.state).project();
+                (this.poll)(cx, state, &mut this.enabled, DISABLED)
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Poll for the next branch to resolve in this [Select], resolving to
+    /// [`None`] once all branches have been disabled.
+    pub fn poll_try_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<O>> {
         // SAFETY: Select is safely pinned.
         unsafe {
             let this = Pin::get_unchecked_mut(self);
-            let mut state = Pin::new_unchecked(&mut this.state);
+            let mut state = Pin::new_unchecked(&mut this.state).project();
 
-            // All branches are disabled.
             for index in this.bias.apply(this.enabled) {
                 if let Poll::Ready(output) =
                     (this.poll)(cx, state.as_mut(), &mut this.enabled, index)
                 {
-                    return Poll::Ready(output);
+                    return Poll::Ready(Some(output));
                 }
             }
 
             // We've polled through all branches (and they have been disabled
             // through pattern matching).
             if this.enabled.is_empty() {
-                return (this.poll)(cx, state.as_mut(), &mut this.enabled, DISABLED);
+                return Poll::Ready(None);
             }
 
             Poll::Pending
@@ -167,6 +444,47 @@ where
     }
 }
 
+struct TryNext<'a, Bits, S, B, T> {
+    this: Pin<&'a mut Select<Bits, S, B, T>>,
+}
+
+impl<Bits, S, B, T, O> Future for TryNext<'_, Bits, S, B, T>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    T: FnMut(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
+{
+    type Output = Option<O>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        unsafe { Pin::get_unchecked_mut(self).this.as_mut().poll_try_next(cx) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct NextBatch<'a, Bits, S, B, T> {
+    this: Pin<&'a mut Select<Bits, S, B, T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<Bits, S, B, T, O> Future for NextBatch<'_, Bits, S, B, T>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    T: FnMut(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
+{
+    type Output = Vec<O>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        unsafe {
+            Pin::get_unchecked_mut(self)
+                .this
+                .as_mut()
+                .poll_ready_batch(cx)
+        }
+    }
+}
+
 impl<Bits, S, B, O> fmt::Debug for Select<Bits, S, B, O>
 where
     Bits: Number,
@@ -176,7 +494,7 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Select")
             .field("enabled", &self.enabled)
-            .field("state", &self.state)
+            .field("state", self.state.get())
             .field("bias", &self.bias)
             .finish()
     }
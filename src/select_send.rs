@@ -0,0 +1,395 @@
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::cancel_safe::CancelSafe;
+
+/// A channel sender which can be used in a `send(<sender>, <value>) =>`
+/// branch.
+///
+/// The branch calls `<sender>.reserve()` and polls the returned future until
+/// it has reserved a [permit][SelectSend::Permit], which is then used to send
+/// the value if the branch wins. If the channel is closed, the future resolves
+/// to `None` and the branch is disabled.
+///
+/// If the `tokio` feature is enabled, this is implemented for borrowed and
+/// owned Tokio `mpsc::Sender`s, so that they can be used in generic code
+/// bounded by this trait. Since the futures returned by Tokio can't be named,
+/// those implementations box them. The future is called through a method
+/// though, so a borrowed sender in a branch is instead supported directly
+/// through its own `reserve` method, whose future is stored in the select
+/// as-is without being boxed.
+///
+/// # Examples
+///
+/// ```
+/// use tokio::sync::mpsc;
+///
+/// # #[cfg(not(feature = "tokio"))] fn main() {}
+/// # #[cfg(feature = "tokio")]
+/// # #[selectme::main] async fn main() {
+/// let (tx, mut rx) = mpsc::channel(1);
+/// let message = String::from("hello");
+///
+/// selectme::select! {
+///     send(&tx, message) => {}
+/// }
+///
+/// assert_eq!(rx.recv().await.as_deref(), Some("hello"));
+/// # }
+/// ```
+pub trait SelectSend {
+    /// The permit which has been reserved.
+    type Permit: SendPermit;
+
+    /// The future reserving a permit.
+    type Reserve: Future<Output = Option<Self::Permit>>;
+
+    /// Construct a future which reserves a permit to send a value, or
+    /// resolves to `None` if the channel is closed.
+    fn reserve(self) -> Self::Reserve;
+}
+
+/// A permit reserved through [SelectSend], which is used to send a single
+/// value.
+pub trait SendPermit {
+    /// The value sent through the permit.
+    type Item;
+
+    /// Send the value using the reserved permit.
+    fn send(self, value: Self::Item);
+}
+
+/// The output of a future reserving a permit, which is either an optional
+/// permit as returned by [SelectSend::reserve] or the result returned by a
+/// Tokio `mpsc::Sender`.
+pub trait IntoPermit {
+    /// The permit which has been reserved.
+    type Permit: SendPermit;
+
+    /// Convert into a permit, or `None` if the channel is closed.
+    fn into_permit(self) -> Option<Self::Permit>;
+}
+
+impl<P> IntoPermit for Option<P>
+where
+    P: SendPermit,
+{
+    type Permit = P;
+
+    #[inline]
+    fn into_permit(self) -> Option<Self::Permit> {
+        self
+    }
+}
+
+impl<P, E> IntoPermit for Result<P, E>
+where
+    P: SendPermit,
+{
+    type Permit = P;
+
+    #[inline]
+    fn into_permit(self) -> Option<Self::Permit> {
+        self.ok()
+    }
+}
+
+/// The future of a send branch in [select!][crate::select!], which reserves a
+/// permit. The value is sent through the permit by the handler of the branch.
+pub struct Reserve<F> {
+    future: F,
+}
+
+impl<F> Reserve<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self { future }
+    }
+}
+
+impl<F> Future for Reserve<F>
+where
+    F: Future,
+    F::Output: IntoPermit,
+{
+    type Output = Option<<F::Output as IntoPermit>::Permit>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The future is structurally pinned.
+        let future = unsafe { Pin::map_unchecked_mut(self, |this| &mut this.future) };
+
+        match future.poll(cx) {
+            Poll::Ready(output) => Poll::Ready(output.into_permit()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F> fmt::Debug for Reserve<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reserve").finish_non_exhaustive()
+    }
+}
+
+impl<F> CancelSafe for Reserve<F> where F: CancelSafe {}
+
+/// The state of a send branch in [inline!][crate::inline!], which holds on to
+/// the value until a permit has been reserved and then sends it.
+///
+/// The value is evaluated when the select is constructed, since the handlers
+/// of an [inline!][crate::inline!] select can't move out of their environment.
+/// If the branch hasn't sent it, for example because another branch completed
+/// first or because the channel was closed, the value can be taken back
+/// through [Select::branch][crate::Select::branch].
+///
+/// # Examples
+///
+/// ```
+/// use std::future::ready;
+///
+/// use tokio::sync::mpsc;
+///
+/// # #[cfg(not(feature = "tokio"))] fn main() {}
+/// # #[cfg(feature = "tokio")]
+/// # #[selectme::main] async fn main() {
+/// let (tx, _rx) = mpsc::channel(1);
+/// tx.send(String::from("first")).await.unwrap();
+///
+/// let message = String::from("second");
+///
+/// let output = selectme::inline! {
+///     biased;
+///
+///     send(&tx, message) => "sent",
+///     () = ready(()) => "ready",
+/// };
+///
+/// tokio::pin!(output);
+///
+/// assert_eq!(output.as_mut().next().await, "ready");
+/// assert_eq!(output.as_mut().branch::<0>().take_value().as_deref(), Some("second"));
+/// # }
+/// ```
+pub struct Sending<F, T> {
+    future: F,
+    value: Option<T>,
+}
+
+impl<F, T> Sending<F, T> {
+    pub(crate) fn new(future: F, value: T) -> Self {
+        Self {
+            future,
+            value: Some(value),
+        }
+    }
+
+    /// Take back the value if it hasn't been sent. Once taken the branch
+    /// completes without sending anything.
+    pub fn take_value(self: Pin<&mut Self>) -> Option<T> {
+        // SAFETY: The value is not structurally pinned.
+        unsafe { Pin::get_unchecked_mut(self).value.take() }
+    }
+}
+
+impl<F, T> Future for Sending<F, T>
+where
+    F: Future,
+    F::Output: IntoPermit,
+    <F::Output as IntoPermit>::Permit: SendPermit<Item = T>,
+{
+    type Output = Option<()>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The future is structurally pinned, the value is not.
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+
+        let permit = match future.poll(cx) {
+            Poll::Ready(output) => output.into_permit(),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        // NB: If the channel is closed, the value is kept so that it can be
+        // taken back.
+        match (permit, this.value.take()) {
+            (Some(permit), Some(value)) => {
+                permit.send(value);
+                Poll::Ready(Some(()))
+            }
+            (_, value) => {
+                this.value = value;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+// NB: `Sending` owns the value until it's sent, so dropping it drops the
+// value with it. An unsent value can only be recovered through `take_value`
+// while the select which holds the branch is still alive.
+impl<F, T> CancelSafe for Sending<F, T> where F: CancelSafe {}
+
+impl<F, T> fmt::Debug for Sending<F, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sending")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub use self::tokio_impls::{SenderReserve, SenderReserveOwned};
+
+#[cfg(feature = "tokio")]
+mod tokio_impls {
+    use alloc::boxed::Box;
+    use core::fmt;
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+
+    use tokio::sync::mpsc::error::SendError;
+    use tokio::sync::mpsc::{OwnedPermit, Permit, Sender};
+
+    use super::{SelectSend, SendPermit};
+    use crate::cancel_safe::CancelSafe;
+
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SendError<()>>> + Send + 'a>>;
+
+    impl<T> SendPermit for Permit<'_, T> {
+        type Item = T;
+
+        #[inline]
+        fn send(self, value: Self::Item) {
+            Permit::send(self, value);
+        }
+    }
+
+    impl<T> SendPermit for OwnedPermit<T> {
+        type Item = T;
+
+        #[inline]
+        fn send(self, value: Self::Item) {
+            OwnedPermit::send(self, value);
+        }
+    }
+
+    /// The future reserving a permit through a borrowed Tokio
+    /// [Sender][tokio::sync::mpsc::Sender] in generic code bounded by
+    /// [SelectSend].
+    ///
+    /// Since the future returned by Tokio can't be named, it's boxed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use selectme::{SelectSend, SendPermit};
+    /// use tokio::sync::mpsc;
+    ///
+    /// async fn send_or<S>(sender: S, value: <S::Permit as SendPermit>::Item) -> bool
+    /// where
+    ///     S: SelectSend,
+    /// {
+    ///     selectme::select! {
+    ///         send(sender, value) => true,
+    ///         else => false,
+    ///     }
+    /// }
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let (tx, mut rx) = mpsc::channel(1);
+    /// assert!(send_or(&tx, 42).await);
+    /// assert_eq!(rx.recv().await, Some(42));
+    /// # }
+    /// ```
+    pub struct SenderReserve<'a, T> {
+        future: BoxFuture<'a, Permit<'a, T>>,
+    }
+
+    impl<'a, T> Future for SenderReserve<'a, T> {
+        type Output = Option<Permit<'a, T>>;
+
+        #[inline]
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            match self.future.as_mut().poll(cx) {
+                Poll::Ready(result) => Poll::Ready(result.ok()),
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for SenderReserve<'_, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SenderReserve").finish_non_exhaustive()
+        }
+    }
+
+    // NB: Reserving a permit doesn't consume any data, so dropping the future
+    // only gives up its place in the queue of senders.
+    impl<T> CancelSafe for SenderReserve<'_, T> {}
+
+    impl<'a, T> SelectSend for &'a Sender<T>
+    where
+        T: Send,
+    {
+        type Permit = Permit<'a, T>;
+        type Reserve = SenderReserve<'a, T>;
+
+        #[inline]
+        fn reserve(self) -> Self::Reserve {
+            SenderReserve {
+                future: Box::pin(Sender::reserve(self)),
+            }
+        }
+    }
+
+    /// The future reserving a permit through an owned Tokio
+    /// [Sender][tokio::sync::mpsc::Sender], which is used when the sender is
+    /// passed by value to a `send(<sender>, <value>)` branch.
+    ///
+    /// Since the future returned by Tokio can't be named, it's boxed.
+    pub struct SenderReserveOwned<T> {
+        future: BoxFuture<'static, OwnedPermit<T>>,
+    }
+
+    impl<T> Future for SenderReserveOwned<T> {
+        type Output = Option<OwnedPermit<T>>;
+
+        #[inline]
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            match self.future.as_mut().poll(cx) {
+                Poll::Ready(result) => Poll::Ready(result.ok()),
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for SenderReserveOwned<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SenderReserveOwned").finish_non_exhaustive()
+        }
+    }
+
+    impl<T> CancelSafe for SenderReserveOwned<T> {}
+
+    impl<T> SelectSend for Sender<T>
+    where
+        T: 'static + Send,
+    {
+        type Permit = OwnedPermit<T>;
+        type Reserve = SenderReserveOwned<T>;
+
+        #[inline]
+        fn reserve(self) -> Self::Reserve {
+            SenderReserveOwned {
+                future: Box::pin(Sender::reserve_owned(self)),
+            }
+        }
+    }
+}
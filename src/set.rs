@@ -17,6 +17,18 @@ where
         self.state.unset(index);
     }
 
+    /// Set the given index.
+    #[inline]
+    pub fn set(&mut self, index: u32) {
+        self.state.set(index);
+    }
+
+    /// Test if the set contains the given index.
+    #[inline]
+    pub fn contains(&self, index: u32) -> bool {
+        self.state.test(index)
+    }
+
     /// Construct a new snapshot with the specified `value`.
     #[inline]
     pub(crate) fn new(state: T) -> Self {
@@ -29,6 +41,12 @@ where
         self.state
     }
 
+    /// Get the number of indexes in the set.
+    #[inline]
+    pub(crate) fn len(&self) -> u32 {
+        self.state.count_ones()
+    }
+
     /// Test if the set is empty.
     #[inline]
     pub(crate) fn is_empty(&self) -> bool {
@@ -85,6 +103,15 @@ pub trait Number: Sized + Copy {
     /// Test if the current number is zero.
     fn is_zero(self) -> bool;
 
+    /// Count the number of set bits.
+    fn count_ones(self) -> u32;
+
+    /// Test if the given bit is set.
+    fn test(self, index: u32) -> bool;
+
+    /// Set the given bit.
+    fn set(&mut self, index: u32);
+
     /// Unset the given bit.
     fn unset(&mut self, index: u32);
 
@@ -112,6 +139,18 @@ macro_rules! number {
                 self == 0
             }
 
+            fn count_ones(self) -> u32 {
+                <$ty>::count_ones(self)
+            }
+
+            fn test(self, index: u32) -> bool {
+                self & (1 << index) != 0
+            }
+
+            fn set(&mut self, index: u32) {
+                *self |= 1 << index;
+            }
+
             fn unset(&mut self, index: u32) {
                 *self &= !(1 << index);
             }
@@ -0,0 +1,251 @@
+use core::fmt;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::pin::Pin;
+use core::ptr;
+
+use crate::set::{Number, Set};
+use crate::state::StateField;
+
+/// The storage of a branch with an `if` precondition.
+///
+/// A slot doesn't keep track of whether it's been initialized. This is instead
+/// done by the [Select][crate::Select] or [StaticSelect][crate::StaticSelect]
+/// which owns it, which avoids storing a discriminant for every conditional
+/// branch.
+///
+/// Empty slots are used when naming the state passed to
+/// [StaticSelect::reset][crate::StaticSelect::reset], and can be populated
+/// through [Select::set_branch][crate::Select::set_branch] or
+/// [StaticSelect::set_branch][crate::StaticSelect::set_branch].
+pub struct Slot<T> {
+    value: MaybeUninit<T>,
+}
+
+impl<T> Slot<T> {
+    /// Construct an empty slot.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self {
+            value: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Construct an initialized slot.
+    #[inline]
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            value: MaybeUninit::new(value),
+        }
+    }
+
+    /// Project the pinned slot into its value.
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized.
+    #[inline]
+    pub(crate) unsafe fn project(self: Pin<&mut Self>) -> Pin<&mut T> {
+        Pin::map_unchecked_mut(self, |slot| slot.value.assume_init_mut())
+    }
+
+    /// Drop the value of the slot in place.
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized, and is no longer initialized once this
+    /// returns.
+    #[inline]
+    pub(crate) unsafe fn drop_in_place(&mut self) {
+        ptr::drop_in_place(self.value.as_mut_ptr());
+    }
+}
+
+impl<T> fmt::Debug for Slot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slot").finish_non_exhaustive()
+    }
+}
+
+/// Function generated to drop the state of a select which has conditional
+/// branches, given the set of branches which are initialized.
+pub(crate) type DropFn<Bits, S> = fn(Pin<&mut S>, &Set<Bits>);
+
+/// Drops a field of the state of a select once the guard is dropped.
+///
+/// The generated functions which drop the state collect one guard for every
+/// field into a tuple, so that if dropping a field panics the remaining ones
+/// are still dropped while unwinding.
+pub struct DropGuard<T> {
+    value: *mut T,
+    drop: unsafe fn(*mut T),
+}
+
+impl<T> DropGuard<T> {
+    /// Construct a guard which drops `value` in place.
+    ///
+    /// # Safety
+    ///
+    /// The value must be initialized, and must not be used again once the
+    /// guard has been dropped.
+    #[inline]
+    pub unsafe fn new(value: *mut T) -> Self {
+        Self {
+            value,
+            drop: ptr::drop_in_place::<T>,
+        }
+    }
+}
+
+impl<T> DropGuard<Slot<T>> {
+    /// Construct a guard which drops the value of `slot` in place.
+    ///
+    /// # Safety
+    ///
+    /// The slot must be initialized, and is no longer initialized once the
+    /// guard has been dropped.
+    #[inline]
+    pub unsafe fn slot(slot: *mut Slot<T>) -> Self {
+        Self {
+            value: slot,
+            drop: |slot| (*slot).drop_in_place(),
+        }
+    }
+}
+
+impl<T> Drop for DropGuard<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: The caller of the constructor guarantees that the value is
+        // initialized and not used again.
+        unsafe { (self.drop)(self.value) }
+    }
+}
+
+impl<T> fmt::Debug for DropGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DropGuard").finish_non_exhaustive()
+    }
+}
+
+/// The state of a select, which keeps track of which branches are initialized.
+pub(crate) struct State<Bits, S> {
+    initialized: Set<Bits>,
+    drop: Option<DropFn<Bits, S>>,
+    state: ManuallyDrop<S>,
+}
+
+impl<Bits, S> State<Bits, S> {
+    #[inline]
+    pub(crate) fn new(initialized: Set<Bits>, state: S, drop: Option<DropFn<Bits, S>>) -> Self {
+        Self {
+            initialized,
+            drop,
+            state: ManuallyDrop::new(state),
+        }
+    }
+
+    /// Access the state.
+    #[inline]
+    pub(crate) fn get(&self) -> &S {
+        &self.state
+    }
+
+    /// Access the pinned state.
+    #[inline]
+    pub(crate) fn project(self: Pin<&mut Self>) -> Pin<&mut S> {
+        // SAFETY: The state is structurally pinned.
+        unsafe { Pin::map_unchecked_mut(self, |this| &mut *this.state) }
+    }
+
+    /// The set of initialized branches.
+    #[inline]
+    pub(crate) fn initialized(&self) -> Set<Bits>
+    where
+        Bits: Number,
+    {
+        self.initialized
+    }
+
+    /// Replace the value of the conditional branch with index `N`, dropping
+    /// the previous value if it's initialized.
+    pub(crate) fn set_slot<const N: usize, F>(self: Pin<&mut Self>, value: F)
+    where
+        Bits: Number,
+        S: StateField<N, Output = Slot<F>>,
+    {
+        let index = N as u32;
+
+        // SAFETY: The slot is dropped in place before being overwritten, and
+        // it's marked as uninitialized while being dropped in case dropping it
+        // panics.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            let state = Pin::new_unchecked(&mut *this.state);
+            let slot = Pin::get_unchecked_mut(StateField::project(state));
+
+            if this.initialized.contains(index) {
+                this.initialized.clear(index);
+                slot.drop_in_place();
+            }
+
+            ptr::write(slot, Slot::new(value));
+            this.initialized.set(index);
+        }
+    }
+
+    /// Drop the current state in place and replace it with `state`, where
+    /// `initialized` is the set of initialized branches in the new state.
+    pub(crate) fn replace(self: Pin<&mut Self>, state: S, initialized: Set<Bits>) {
+        /// Writes the new state once the old one has been dropped, even if
+        /// dropping it panics.
+        struct Guard<'a, Bits, S> {
+            this: &'a mut State<Bits, S>,
+            state: ManuallyDrop<S>,
+            initialized: Set<Bits>,
+        }
+
+        impl<Bits, S> Drop for Guard<'_, Bits, S> {
+            fn drop(&mut self) {
+                // SAFETY: The new state is only taken once.
+                let state = unsafe { ManuallyDrop::take(&mut self.state) };
+                self.this.state = ManuallyDrop::new(state);
+                mem::swap(&mut self.this.initialized, &mut self.initialized);
+            }
+        }
+
+        // SAFETY: The state is dropped in place and replaced without being
+        // moved.
+        unsafe {
+            let guard = Guard {
+                this: Pin::get_unchecked_mut(self),
+                state: ManuallyDrop::new(state),
+                initialized,
+            };
+
+            guard.this.drop_in_place();
+        }
+    }
+
+    /// Drop the state in place.
+    ///
+    /// # Safety
+    ///
+    /// The state must not be used again until it's been re-initialized.
+    unsafe fn drop_in_place(&mut self) {
+        match self.drop {
+            Some(drop) => {
+                drop(Pin::new_unchecked(&mut *self.state), &self.initialized);
+            }
+            None => {
+                ManuallyDrop::drop(&mut self.state);
+            }
+        }
+    }
+}
+
+impl<Bits, S> Drop for State<Bits, S> {
+    fn drop(&mut self) {
+        // SAFETY: The state is pinned and never used again.
+        unsafe { self.drop_in_place() }
+    }
+}
@@ -0,0 +1,67 @@
+use core::pin::Pin;
+
+/// Typed access to the state of the branch with index `N`.
+///
+/// This is implemented for the state tuples generated by the
+/// [inline!][crate::inline!] macro, including when they are boxed through the
+/// `boxed;` option. Since it is only implemented for tuples of up to 16
+/// elements, [inline!][crate::inline!] rejects selects with more branches.
+/// Branches with an `if` precondition are stored in a [Slot][crate::Slot],
+/// which can be populated through
+/// [Select::set_branch][crate::Select::set_branch] or
+/// [StaticSelect::set_branch][crate::StaticSelect::set_branch].
+pub trait StateField<const N: usize> {
+    /// The type of the branch state.
+    type Output;
+
+    /// Project the pinned state into the state of the branch.
+    fn project(self: Pin<&mut Self>) -> Pin<&mut Self::Output>;
+}
+
+#[cfg(feature = "alloc")]
+impl<S, const N: usize> StateField<N> for Pin<alloc::boxed::Box<S>>
+where
+    S: StateField<N>,
+{
+    type Output = S::Output;
+
+    #[inline]
+    fn project(self: Pin<&mut Self>) -> Pin<&mut Self::Output> {
+        S::project(Pin::get_mut(self).as_mut())
+    }
+}
+
+macro_rules! field {
+    ($all:tt $($n:tt $t:ident),*) => {
+        $(field!(@impl $all $n $t);)*
+    };
+
+    (@impl ($($all:ident),*) $n:tt $t:ident) => {
+        impl<$($all),*> StateField<$n> for ($($all,)*) {
+            type Output = $t;
+
+            #[inline]
+            fn project(self: Pin<&mut Self>) -> Pin<&mut Self::Output> {
+                // SAFETY: The fields of the state are structurally pinned.
+                unsafe { Pin::map_unchecked_mut(self, |state| &mut state.$n) }
+            }
+        }
+    };
+}
+
+field!((T0) 0 T0);
+field!((T0, T1) 0 T0, 1 T1);
+field!((T0, T1, T2) 0 T0, 1 T1, 2 T2);
+field!((T0, T1, T2, T3) 0 T0, 1 T1, 2 T2, 3 T3);
+field!((T0, T1, T2, T3, T4) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4);
+field!((T0, T1, T2, T3, T4, T5) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5);
+field!((T0, T1, T2, T3, T4, T5, T6) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6);
+field!((T0, T1, T2, T3, T4, T5, T6, T7) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14);
+field!((T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15) 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8, 9 T9, 10 T10, 11 T11, 12 T12, 13 T13, 14 T14, 15 T15);
@@ -1,15 +1,124 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::fmt;
 use core::future::Future;
+use core::marker::PhantomData;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
 use crate::bias::Bias;
+use crate::branch::SelectBranch;
 use crate::select::DISABLED;
 use crate::set::{Number, Set};
+use crate::slot::{Slot, State};
+use crate::state::StateField;
 
-/// The type of a static poller function. This is produced when
-/// [inline!][crate::inline!] is used with the `static` option.
-type StaticPoll<Bits, S, O> = fn(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>;
+/// A function pointer which can be used as a [Poller].
+///
+/// This is the default poller of a [StaticSelect], which makes it possible to
+/// name the select through the type of its output alone. The poller generated
+/// by [inline!][crate::inline!] with the `static;` option coerces into this
+/// type if it is expected, at the cost of polling branches through an
+/// indirect call. Since the poller has to be `'static`, so does the state of a
+/// select which is named this way.
+///
+/// # Examples
+///
+/// ```
+/// use std::future::{ready, Ready};
+///
+/// use selectme::{Random, StaticSelect};
+///
+/// type State = (Ready<u32>, Ready<u32>);
+///
+/// # #[selectme::main] async fn main() {
+/// let select: StaticSelect<u8, State, Random, Option<u32>> = selectme::inline! {
+///     static;
+///
+///     value = ready(1) => Some(value),
+///     value = ready(2) => Some(value),
+///     else => None,
+/// };
+///
+/// assert!(select.await.is_some());
+/// # }
+/// ```
+pub type PollFn<Bits, S, O, C = ()> =
+    fn(&mut Context<'_>, &mut C, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>;
+
+/// A statically dispatched poller used by [StaticSelect].
+///
+/// This is implemented for the zero-sized poller generated by
+/// [inline!][crate::inline!] when the `static;` option is enabled, and for
+/// [PollFn] function pointers.
+///
+/// The `C` parameter is the type of the context which is passed to handlers
+/// when the `context: <type>;` option is used, see
+/// [StaticSelect::poll_next_with].
+///
+/// # Examples
+///
+/// The generated poller can't capture its environment, so a select can be
+/// returned from a function without going through a [PollFn], which keeps
+/// its branches statically dispatched.
+///
+/// ```
+/// use std::future::{ready, Ready};
+///
+/// use selectme::{Poller, Random, StaticSelect};
+///
+/// type State = (Ready<u32>, Ready<u32>);
+///
+/// fn select(
+///     a: u32,
+///     b: u32,
+/// ) -> StaticSelect<u8, State, Random, Option<u32>, impl Poller<u8, State, Output = Option<u32>>> {
+///     selectme::inline! {
+///         static;
+///
+///         value = ready(a) => Some(value),
+///         value = ready(b) => Some(value),
+///         else => None,
+///     }
+/// }
+///
+/// # #[selectme::main] async fn main() {
+/// assert!(select(1, 2).await.is_some());
+/// # }
+/// ```
+pub trait Poller<Bits, S, C = ()> {
+    /// The output of the selection.
+    type Output;
+
+    /// Poll the branch with the given `index`.
+    fn poll(
+        &self,
+        cx: &mut Context<'_>,
+        context: &mut C,
+        state: Pin<&mut S>,
+        mask: &mut Set<Bits>,
+        index: u32,
+    ) -> Poll<Self::Output>;
+}
+
+impl<Bits, S, C, O, F> Poller<Bits, S, C> for F
+where
+    F: Fn(&mut Context<'_>, &mut C, Pin<&mut S>, &mut Set<Bits>, u32) -> Poll<O>,
+{
+    type Output = O;
+
+    #[inline]
+    fn poll(
+        &self,
+        cx: &mut Context<'_>,
+        context: &mut C,
+        state: Pin<&mut S>,
+        mask: &mut Set<Bits>,
+        index: u32,
+    ) -> Poll<Self::Output> {
+        self(cx, context, state, mask, index)
+    }
+}
 
 /// The implementation used by the [select!][crate::select!] macro internally
 /// and returned by the [inline!][crate::inline!] macro when the `static;`
@@ -18,6 +127,11 @@ type StaticPoll<Bits, S, O> = fn(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>,
 /// See the [select!][crate::select!] and [inline!][crate::inline!] macros for
 /// documentation on syntax and use.
 ///
+/// The last parameter is the [Poller] of the select, which defaults to a
+/// [PollFn] so that the select can be named through the type of its output
+/// `O`. If it's left to be inferred, the zero-sized poller generated by
+/// [inline!][crate::inline!] is used instead.
+///
 /// # Examples
 ///
 /// ```
@@ -62,28 +176,268 @@ type StaticPoll<Bits, S, O> = fn(&mut Context<'_>, Pin<&mut S>, &mut Set<Bits>,
 /// assert_eq!(my_future.await, Some(1));
 /// # }
 /// ```
-pub struct StaticSelect<Bits, S, B, O> {
+pub struct StaticSelect<Bits, S, B, O, P = PollFn<Bits, S, O>> {
     enabled: Set<Bits>,
-    state: S,
+    unconditional: Set<Bits>,
+    state: State<Bits, S>,
     bias: B,
-    poll: StaticPoll<Bits, S, O>,
+    poll: P,
+    _marker: PhantomData<fn() -> O>,
 }
 
-impl<Bits, S, B, O> StaticSelect<Bits, S, B, O> {
-    pub(crate) fn new(enabled: Set<Bits>, bias: B, state: S, poll: StaticPoll<Bits, S, O>) -> Self {
+impl<Bits, S, B, O, P> StaticSelect<Bits, S, B, O, P> {
+    pub(crate) fn new(
+        enabled: Set<Bits>,
+        unconditional: Set<Bits>,
+        bias: B,
+        state: State<Bits, S>,
+        poll: P,
+    ) -> Self {
         Self {
             enabled,
+            unconditional,
             state,
             bias,
             poll,
+            _marker: PhantomData,
         }
     }
+
+    /// Access the poller of the select.
+    #[inline]
+    pub(crate) fn poller(&self) -> &P {
+        &self.poll
+    }
 }
 
-impl<Bits, S, B, O> StaticSelect<Bits, S, B, O>
+impl<Bits, S, B, O, P> StaticSelect<Bits, S, B, O, P>
+where
+    Bits: Number,
+{
+    /// Test if all branches have been disabled.
+    ///
+    /// Once this returns `true`, [StaticSelect::try_next] will resolve to
+    /// [`None`].
+    pub fn is_terminated(&self) -> bool {
+        self.enabled.is_empty()
+    }
+
+    /// Get the number of branches which are still enabled.
+    pub fn remaining(&self) -> u32 {
+        self.enabled.len()
+    }
+
+    /// Disable the branch with the given `index`, causing it to no longer be
+    /// polled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the branch mask.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///     biased;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     value = ready(2) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().disable(0);
+    /// assert_eq!(output.as_mut().next().await, Some(2));
+    /// assert_eq!(output.as_mut().next().await, None);
+    /// # }
+    /// ```
+    pub fn disable(self: Pin<&mut Self>, index: u32) {
+        assert!(index < Bits::BITS, "branch index out of bounds");
+        // SAFETY: The mask is not structurally pinned.
+        unsafe { Pin::get_unchecked_mut(self).enabled.clear(index) }
+    }
+
+    /// Enable the branch with the given `index`, causing it to be polled again.
+    ///
+    /// Care must be taken to only enable branches which can be polled. A
+    /// branch which has already completed will typically panic if it is polled
+    /// again unless it has been replaced through
+    /// [StaticSelect::set_branch].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the branch mask, or if it's a
+    /// conditional branch which is empty.
+    pub fn enable(self: Pin<&mut Self>, index: u32) {
+        assert!(index < Bits::BITS, "branch index out of bounds");
+
+        // SAFETY: The mask is not structurally pinned.
+        let this = unsafe { Pin::get_unchecked_mut(self) };
+
+        assert!(
+            this.state.initialized().contains(index),
+            "branch `{index}` is empty"
+        );
+
+        this.enabled.set(index);
+    }
+
+    /// Access the pinned branch with index `N`, which is usually a future.
+    ///
+    /// Note that this does not enable or disable the branch. Conditional
+    /// branches can't be accessed like this, instead use
+    /// [StaticSelect::set_branch] to replace their future.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().branch::<0>().set(ready(42));
+    /// assert_eq!(output.as_mut().next().await, Some(42));
+    /// # }
+    /// ```
+    pub fn branch<const N: usize>(self: Pin<&mut Self>) -> Pin<&mut S::Output>
+    where
+        S: StateField<N>,
+        S::Output: SelectBranch,
+    {
+        // SAFETY: The state is structurally pinned.
+        unsafe {
+            let state = Pin::new_unchecked(&mut Pin::get_unchecked_mut(self).state);
+            StateField::project(State::project(state))
+        }
+    }
+
+    /// Replace the future of the branch with index `N` and enable it.
+    ///
+    /// This is only supported for branches which are stored in a [Slot], which
+    /// is the case for branches which have an `if` precondition. A
+    /// precondition of `if false` can be used to declare a branch which
+    /// starts out empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///
+    ///     value = ready(0), if false => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// output.as_mut().set_branch::<0, _>(ready(42));
+    /// assert_eq!(output.as_mut().next().await, Some(42));
+    /// assert_eq!(output.as_mut().next().await, None);
+    /// # }
+    /// ```
+    pub fn set_branch<const N: usize, F>(self: Pin<&mut Self>, future: F)
+    where
+        S: StateField<N, Output = Slot<F>>,
+    {
+        // SAFETY: The state is structurally pinned, the mask is not.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            Pin::new_unchecked(&mut this.state).set_slot::<N, F>(future);
+            this.enabled.set(N as u32);
+        }
+    }
+
+    /// Replace the bias used when selecting the order in which branches are
+    /// polled.
+    pub fn set_bias(self: Pin<&mut Self>, bias: B) {
+        // SAFETY: The bias is not structurally pinned.
+        unsafe { Pin::get_unchecked_mut(self).bias = bias }
+    }
+
+    /// Reinitialize the select in place with a new `state`.
+    ///
+    /// This drops the current branch futures, enables all branches without an
+    /// `if` precondition again and redraws the bias if it's random.
+    ///
+    /// Branches with an `if` precondition are stored in a [Slot], which starts
+    /// out empty after a reset. They can then be populated through
+    /// [StaticSelect::set_branch].
+    ///
+    /// This makes it possible to embed a single long-lived [StaticSelect] in a
+    /// future instead of constructing a new one every time it completes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// use selectme::Slot;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     value = ready(2), if false => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// assert_eq!(output.as_mut().next().await, Some(1));
+    /// assert_eq!(output.as_mut().next().await, None);
+    ///
+    /// for n in 0..3 {
+    ///     output.as_mut().reset((ready(n), Slot::empty()));
+    ///     output.as_mut().set_branch::<1, _>(ready(10));
+    ///
+    ///     let mut values = Vec::new();
+    ///
+    ///     while let Some(value) = output.as_mut().next().await {
+    ///         values.push(value);
+    ///     }
+    ///
+    ///     values.sort();
+    ///     assert_eq!(values, &[n, 10]);
+    /// }
+    /// # }
+    /// ```
+    pub fn reset(self: Pin<&mut Self>, state: S)
+    where
+        B: Bias<Bits>,
+    {
+        // SAFETY: The state is structurally pinned and dropped in place, the
+        // rest of the fields are not structurally pinned.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            Pin::new_unchecked(&mut this.state).replace(state, this.unconditional);
+            this.enabled = this.unconditional;
+            this.bias.redraw();
+        }
+    }
+}
+
+impl<Bits, S, B, O, P> StaticSelect<Bits, S, B, O, P>
 where
     Bits: Number,
     B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
 {
     /// Get the next element from this select when pinned.
     ///
@@ -122,26 +476,231 @@ where
         Next { this: self }.await
     }
 
+    /// Get the next element from this select when pinned, or [`None`] if all
+    /// branches have been disabled.
+    ///
+    /// Unlike [StaticSelect::next] this does not evaluate the `else` branch once
+    /// all branches have been disabled, which makes it possible to drain a
+    /// selection which doesn't have one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///
+    ///     value = ready(1) => value,
+    ///     value = ready(2) => value,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// let mut values = Vec::new();
+    ///
+    /// while let Some(value) = output.as_mut().try_next().await {
+    ///     values.push(value);
+    /// }
+    ///
+    /// values.sort();
+    /// assert_eq!(values, &[1, 2]);
+    /// assert!(output.is_terminated());
+    /// # }
+    /// ```
+    pub async fn try_next(self: Pin<&mut Self>) -> Option<O> {
+        TryNext { this: self }.await
+    }
+
+    /// Get every output which is ready in a single pass over the branches of
+    /// this select when pinned.
+    ///
+    /// Unlike [StaticSelect::next] this doesn't stop at the first branch which
+    /// is ready. Every enabled branch is polled once, and the outputs of the
+    /// ones which completed are returned in the order they were polled in, as
+    /// determined by the bias. Once all branches have been disabled the batch
+    /// consists of the output of the `else` branch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::ready;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///     biased;
+    ///
+    ///     value = ready(1) => Some(value),
+    ///     value = ready(2) => Some(value),
+    ///     else => None,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// assert_eq!(output.as_mut().next_batch().await, [Some(1), Some(2)]);
+    /// assert_eq!(output.as_mut().next_batch().await, [None]);
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub async fn next_batch(self: Pin<&mut Self>) -> Vec<O> {
+        NextBatch { this: self }.await
+    }
+
+    /// Poll for every branch which is ready in a single pass over this
+    /// [StaticSelect], see [StaticSelect::next_batch].
+    #[cfg(feature = "alloc")]
+    pub fn poll_ready_batch(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Vec<O>> {
+        self.poll_ready_batch_with(cx, &mut ())
+    }
+
     /// Poll for the next branch to resolve in this [StaticSelect].
     pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<O> {
+        self.poll_next_with(cx, &mut ())
+    }
+
+    /// Poll for the next branch to resolve in this [StaticSelect], resolving to
+    /// [`None`] once all branches have been disabled.
+    pub fn poll_try_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        self.poll_try_next_with(cx, &mut ())
+    }
+}
+
+impl<Bits, S, B, O, P> StaticSelect<Bits, S, B, O, P>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+{
+    /// Poll for the next branch to resolve in this [StaticSelect], passing
+    /// `context` to the handler of the branch.
+    ///
+    /// The type of the context is specified through the `context: <type>;`
+    /// option, and it is available as `context` inside of handlers. This makes
+    /// it possible for a static select to access state which it otherwise
+    /// couldn't capture.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::future::{poll_fn, ready};
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::inline! {
+    ///     static;
+    ///     context: Vec<u32>;
+    ///
+    ///     value = ready(1) => {
+    ///         context.push(value);
+    ///         true
+    ///     }
+    ///     else => false,
+    /// };
+    ///
+    /// tokio::pin!(output);
+    ///
+    /// let mut values = Vec::new();
+    ///
+    /// while poll_fn(|cx| output.as_mut().poll_next_with(cx, &mut values)).await {}
+    ///
+    /// assert_eq!(values, &[1]);
+    /// # }
+    /// ```
+    pub fn poll_next_with<C>(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        context: &mut C,
+    ) -> Poll<O>
+    where
+        P: Poller<Bits, S, C, Output = O>,
+    {
+        match self.as_mut().poll_try_next_with(cx, context) {
+            Poll::Ready(Some(output)) => Poll::Ready(output),
+            // SAFETY: StaticSelect is safely pinned.
+            Poll::Ready(None) => unsafe {
+                let this = Pin::get_unchecked_mut(self);
+                let state = Pin::new_unchecked(&mut this.state).project();
+                this.poll
+                    .poll(cx, context, state, &mut this.enabled, DISABLED)
+            },
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Poll for every branch which is ready in a single pass over this
+    /// [StaticSelect], passing `context` to the handler of each branch which
+    /// completed.
+    #[cfg(feature = "alloc")]
+    pub fn poll_ready_batch_with<C>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        context: &mut C,
+    ) -> Poll<Vec<O>>
+    where
+        P: Poller<Bits, S, C, Output = O>,
+    {
         // SAFETY: StaticSelect is safely pinned.
         unsafe {
             let this = Pin::get_unchecked_mut(self);
-            let mut state = Pin::new_unchecked(&mut this.state);
+            let mut state = Pin::new_unchecked(&mut this.state).project();
+            let mut outputs = Vec::new();
 
-            // All branches are disabled.
             for index in this.bias.apply(this.enabled) {
                 if let Poll::Ready(output) =
-                    (this.poll)(cx, state.as_mut(), &mut this.enabled, index)
+                    this.poll
+                        .poll(cx, context, state.as_mut(), &mut this.enabled, index)
                 {
-                    return Poll::Ready(output);
+                    outputs.push(output);
+                }
+            }
+
+            if !outputs.is_empty() {
+                return Poll::Ready(outputs);
+            }
+
+            if this.enabled.is_empty() {
+                if let Poll::Ready(output) =
+                    this.poll
+                        .poll(cx, context, state, &mut this.enabled, DISABLED)
+                {
+                    outputs.push(output);
+                    return Poll::Ready(outputs);
+                }
+            }
+
+            Poll::Pending
+        }
+    }
+
+    /// Poll for the next branch to resolve in this [StaticSelect], passing
+    /// `context` to the handler of the branch and resolving to [`None`] once
+    /// all branches have been disabled.
+    pub fn poll_try_next_with<C>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        context: &mut C,
+    ) -> Poll<Option<O>>
+    where
+        P: Poller<Bits, S, C, Output = O>,
+    {
+        // SAFETY: StaticSelect is safely pinned.
+        unsafe {
+            let this = Pin::get_unchecked_mut(self);
+            let mut state = Pin::new_unchecked(&mut this.state).project();
+
+            for index in this.bias.apply(this.enabled) {
+                if let Poll::Ready(output) =
+                    this.poll
+                        .poll(cx, context, state.as_mut(), &mut this.enabled, index)
+                {
+                    return Poll::Ready(Some(output));
                 }
             }
 
             // We've polled through all branches (and they have been disabled
             // through pattern matching).
             if this.enabled.is_empty() {
-                return (this.poll)(cx, state.as_mut(), &mut this.enabled, DISABLED);
+                return Poll::Ready(None);
             }
 
             Poll::Pending
@@ -149,10 +708,11 @@ where
     }
 }
 
-impl<Bits, S, B, O> Future for StaticSelect<Bits, S, B, O>
+impl<Bits, S, B, O, P> Future for StaticSelect<Bits, S, B, O, P>
 where
     Bits: Number,
     B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
 {
     type Output = O;
 
@@ -161,14 +721,15 @@ where
     }
 }
 
-struct Next<'a, Bits, S, B, O> {
-    this: Pin<&'a mut StaticSelect<Bits, S, B, O>>,
+struct Next<'a, Bits, S, B, O, P> {
+    this: Pin<&'a mut StaticSelect<Bits, S, B, O, P>>,
 }
 
-impl<Bits, S, B, O> Future for Next<'_, Bits, S, B, O>
+impl<Bits, S, B, O, P> Future for Next<'_, Bits, S, B, O, P>
 where
     Bits: Number,
     B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
 {
     type Output = O;
 
@@ -177,7 +738,48 @@ where
     }
 }
 
-impl<Bits, S, B, O> fmt::Debug for StaticSelect<Bits, S, B, O>
+struct TryNext<'a, Bits, S, B, O, P> {
+    this: Pin<&'a mut StaticSelect<Bits, S, B, O, P>>,
+}
+
+impl<Bits, S, B, O, P> Future for TryNext<'_, Bits, S, B, O, P>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
+{
+    type Output = Option<O>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        unsafe { Pin::get_unchecked_mut(self).this.as_mut().poll_try_next(cx) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct NextBatch<'a, Bits, S, B, O, P> {
+    this: Pin<&'a mut StaticSelect<Bits, S, B, O, P>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<Bits, S, B, O, P> Future for NextBatch<'_, Bits, S, B, O, P>
+where
+    Bits: Number,
+    B: Bias<Bits>,
+    P: Poller<Bits, S, Output = O>,
+{
+    type Output = Vec<O>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        unsafe {
+            Pin::get_unchecked_mut(self)
+                .this
+                .as_mut()
+                .poll_ready_batch(cx)
+        }
+    }
+}
+
+impl<Bits, S, B, O, P> fmt::Debug for StaticSelect<Bits, S, B, O, P>
 where
     Bits: Number,
     B: fmt::Debug,
@@ -186,7 +788,7 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StaticSelect")
             .field("enabled", &self.enabled)
-            .field("state", &self.state)
+            .field("state", self.state.get())
             .field("bias", &self.bias)
             .finish()
     }
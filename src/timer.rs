@@ -0,0 +1,236 @@
+use core::future::Future;
+use core::time::Duration;
+
+/// A timer used to construct the futures of timer branches.
+///
+/// Timer branches are declared as `after(<duration>)` and `at(<deadline>)` in
+/// the [select!][crate::select!] and [inline!][crate::inline!] macros. The
+/// timer to use is specified through the `timer = <expr>;` option, which
+/// defaults to [TokioTimer] if the `tokio` feature is enabled.
+///
+/// [TokioTimer]: https://docs.rs/selectme/latest/selectme/struct.TokioTimer.html
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use selectme::MockTimer;
+///
+/// # #[selectme::main] async fn main() {
+/// let timer = MockTimer::new();
+/// let deadline = timer.now() + Duration::from_secs(5);
+/// timer.advance(Duration::from_secs(10));
+///
+/// let output = selectme::select! {
+///     timer = &timer;
+///
+///     () = std::future::pending::<()>() => "completed",
+///     at(deadline) => "timed out",
+/// };
+///
+/// assert_eq!(output, "timed out");
+/// # }
+/// ```
+pub trait Timer {
+    /// The instant used as a deadline in `at(<deadline>)` branches.
+    type Instant;
+
+    /// The future produced by the timer.
+    type Sleep: Future<Output = ()>;
+
+    /// Construct a future which completes once the given `duration` has
+    /// elapsed.
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+
+    /// Construct a future which completes once the given `deadline` has been
+    /// reached.
+    fn sleep_until(&self, deadline: Self::Instant) -> Self::Sleep;
+}
+
+impl<T> Timer for &T
+where
+    T: ?Sized + Timer,
+{
+    type Instant = T::Instant;
+    type Sleep = T::Sleep;
+
+    #[inline]
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        (**self).sleep(duration)
+    }
+
+    #[inline]
+    fn sleep_until(&self, deadline: Self::Instant) -> Self::Sleep {
+        (**self).sleep_until(deadline)
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub use self::tokio_timer::TokioTimer;
+
+#[cfg(feature = "tokio")]
+mod tokio_timer {
+    use core::time::Duration;
+
+    use tokio::time::{self, Instant, Sleep};
+
+    use super::Timer;
+
+    /// A [Timer] implemented using the Tokio time driver.
+    ///
+    /// This is the default timer if the `tokio` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let output = selectme::select! {
+    ///     () = std::future::pending::<()>() => "completed",
+    ///     after(Duration::from_millis(10)) => "timed out",
+    /// };
+    ///
+    /// assert_eq!(output, "timed out");
+    /// # }
+    /// ```
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct TokioTimer;
+
+    impl Timer for TokioTimer {
+        type Instant = Instant;
+        type Sleep = Sleep;
+
+        #[inline]
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            time::sleep(duration)
+        }
+
+        #[inline]
+        fn sleep_until(&self, deadline: Self::Instant) -> Self::Sleep {
+            time::sleep_until(deadline)
+        }
+    }
+}
+
+#[cfg(feature = "test-util")]
+pub use self::mock::{MockSleep, MockTimer};
+
+#[cfg(feature = "test-util")]
+mod mock {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use core::time::Duration;
+
+    use std::sync::{Arc, Mutex};
+
+    use super::Timer;
+
+    #[derive(Debug, Default)]
+    struct Shared {
+        now: Duration,
+        wakers: Vec<Waker>,
+    }
+
+    /// A [Timer] which only advances when told to, intended for use in tests.
+    ///
+    /// This is only available if the `test-util` feature is enabled.
+    ///
+    /// Instants used by this timer are the [Duration] since the timer was
+    /// constructed. Clones of the timer share the same clock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use selectme::MockTimer;
+    ///
+    /// # #[selectme::main] async fn main() {
+    /// let timer = MockTimer::new();
+    /// timer.advance(Duration::from_secs(1));
+    ///
+    /// let output = selectme::select! {
+    ///     timer = &timer;
+    ///
+    ///     at(Duration::from_secs(2)) => 2,
+    ///     at(Duration::from_secs(1)) => 1,
+    /// };
+    ///
+    /// assert_eq!(output, 1);
+    /// # }
+    /// ```
+    #[derive(Debug, Default, Clone)]
+    pub struct MockTimer {
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl MockTimer {
+        /// Construct a new mock timer starting at a zero duration.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Get the current instant of the timer.
+        pub fn now(&self) -> Duration {
+            self.shared.lock().unwrap().now
+        }
+
+        /// Advance the timer by the given `duration`, waking up any sleeps
+        /// which are waiting on it.
+        pub fn advance(&self, duration: Duration) {
+            let wakers = {
+                let mut shared = self.shared.lock().unwrap();
+                shared.now += duration;
+                core::mem::take(&mut shared.wakers)
+            };
+
+            for waker in wakers {
+                waker.wake();
+            }
+        }
+    }
+
+    impl Timer for MockTimer {
+        type Instant = Duration;
+        type Sleep = MockSleep;
+
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            self.sleep_until(self.now() + duration)
+        }
+
+        fn sleep_until(&self, deadline: Self::Instant) -> Self::Sleep {
+            MockSleep {
+                deadline,
+                shared: self.shared.clone(),
+            }
+        }
+    }
+
+    /// The future produced by [MockTimer].
+    #[derive(Debug)]
+    pub struct MockSleep {
+        deadline: Duration,
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl Future for MockSleep {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut shared = self.shared.lock().unwrap();
+
+            if shared.now >= self.deadline {
+                return Poll::Ready(());
+            }
+
+            if !shared.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                shared.wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
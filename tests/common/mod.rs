@@ -0,0 +1,53 @@
+//! Helpers shared between integration tests.
+
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+/// A future which records its name in a log when it's dropped.
+pub struct Tracked<'a, F> {
+    name: &'static str,
+    log: &'a RefCell<Vec<&'static str>>,
+    future: F,
+}
+
+impl<F> Future for Tracked<'_, F>
+where
+    F: Future + Unpin,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.future).poll(cx)
+    }
+}
+
+impl<F> Drop for Tracked<'_, F> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name);
+    }
+}
+
+/// Wrap a future so that dropping it is recorded in `log`.
+pub fn tracked<'a, F>(
+    name: &'static str,
+    log: &'a RefCell<Vec<&'static str>>,
+    future: F,
+) -> Tracked<'a, F> {
+    Tracked { name, log, future }
+}
+
+/// Construct a waker which does nothing when woken.
+pub fn noop_waker() -> Waker {
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    Arc::new(Noop).into()
+}
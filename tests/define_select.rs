@@ -0,0 +1,63 @@
+use std::future::{pending, ready};
+
+selectme::define_select!(biased_select, biased;);
+
+async fn shutdown() {}
+
+selectme::define_select!(cancel_select, biased; cancel = shutdown(););
+
+selectme::define_select!(default_select, biased; cancel = shutdown(); cancelled => None);
+
+/// Preset options are applied to every use of the defined macro.
+#[selectme::test]
+async fn define_select_biased() {
+    for _ in 0..16 {
+        let output = biased_select! {
+            value = ready(1) => value,
+            value = ready(2) => value,
+        };
+
+        assert_eq!(output, 1);
+    }
+}
+
+/// Additional options can be provided at each use.
+#[selectme::test]
+async fn define_select_options() {
+    let output = biased_select! {
+        boxed;
+
+        value = pending::<u32>() => value,
+        value = ready(2) => value,
+    };
+
+    assert_eq!(output, 2);
+}
+
+/// Preset options can add implicit branches.
+#[selectme::test]
+async fn define_select_cancel() {
+    let output = cancel_select! {
+        value = ready(1) => Some(value),
+        cancelled => None,
+    };
+
+    assert_eq!(output, None);
+}
+
+/// The default `cancelled =>` arm is used unless one is specified.
+#[selectme::test]
+async fn define_select_default_cancelled() {
+    let output = default_select! {
+        value = pending::<u32>() => Some(value),
+    };
+
+    assert_eq!(output, None);
+
+    let output = default_select! {
+        value = pending::<u32>() => Some(value),
+        cancelled => Some(0),
+    };
+
+    assert_eq!(output, Some(0));
+}
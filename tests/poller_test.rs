@@ -26,6 +26,7 @@ async fn poller_test() {
                 initial,
                 ::selectme::__support::unbiased(),
                 __fut,
+                None,
                 |cx, state, mask, index| {
                     match index {
                         0 => {
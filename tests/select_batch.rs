@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use selectme::SelectBranch;
+
+/// A stream-like branch which produces items in bursts, and is pending
+/// between each burst.
+struct Bursts(VecDeque<VecDeque<u32>>);
+
+impl Bursts {
+    fn new<const N: usize>(bursts: [&[u32]; N]) -> Self {
+        Self(bursts.iter().map(|b| b.iter().copied().collect()).collect())
+    }
+}
+
+impl SelectBranch for Bursts {
+    type Output = u32;
+
+    fn poll_branch(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        let burst = match self.0.front_mut() {
+            Some(burst) => burst,
+            None => return Poll::Pending,
+        };
+
+        if let Some(item) = burst.pop_front() {
+            return Poll::Ready(item);
+        }
+
+        self.0.pop_front();
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0.iter().all(|burst| burst.is_empty())
+    }
+}
+
+/// Batches are drained up to the limit or until the branch is pending.
+#[selectme::test]
+async fn select_batch_drain() {
+    let output = selectme::inline! {
+        items = Bursts::new([&[1, 2, 3], &[4, 5], &[6]]), batch(2) => Some(items),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut batches = Vec::new();
+
+    while let Some(items) = output.as_mut().next().await {
+        batches.push(items);
+    }
+
+    assert_eq!(batches, [vec![1, 2], vec![3], vec![4, 5], vec![6]]);
+}
+
+/// A large limit drains everything which is ready.
+#[selectme::test]
+async fn select_batch_large_limit() {
+    let output = selectme::inline! {
+        items = Bursts::new([&[1, 2, 3], &[4, 5], &[6]]), batch(16) => Some(items),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut batches = Vec::new();
+
+    while let Some(items) = output.as_mut().next().await {
+        batches.push(items);
+    }
+
+    assert_eq!(batches, [vec![1, 2, 3], vec![4, 5], vec![6]]);
+}
+
+/// Futures complete once, so they produce a batch with a single item.
+#[selectme::test]
+async fn select_batch_future() {
+    let output = selectme::select! {
+        items = async { 42 }, batch(8) => items,
+    };
+
+    assert_eq!(output, [42]);
+}
+
+/// The limit can be any expression, like a constant.
+#[selectme::test]
+async fn select_batch_const_limit() {
+    const LIMIT: usize = 2;
+
+    let output = selectme::inline! {
+        items = Bursts::new([&[1, 2, 3]]), batch(LIMIT) => Some(items),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next().await, Some(vec![1, 2]));
+}
+
+/// A limit of zero which isn't a literal panics when the select is
+/// constructed.
+#[test]
+#[should_panic = "batch limit must be greater than zero"]
+fn select_batch_zero_limit() {
+    let limit = 0;
+
+    let _output = selectme::inline! {
+        items = Bursts::new([&[1]]), batch(limit) => items,
+    };
+}
@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::future::{pending, ready, Future, Ready};
+use std::mem::size_of_val;
+use std::panic::{self, AssertUnwindSafe};
+
+use common::tracked;
+use selectme::Slot;
+
+mod common;
+
+async fn large(value: u32) -> u32 {
+    let buf = [value; 1024];
+    ready(()).await;
+    buf.iter().sum()
+}
+
+fn size_of_future<F>(future: F) -> usize
+where
+    F: Future,
+{
+    size_of_val(&future)
+}
+
+/// Boxed selects don't store the branch futures inline.
+#[test]
+fn select_boxed_size() {
+    let inline = size_of_future(async {
+        selectme::select! {
+            value = large(1) => value,
+            value = large(2) => value,
+        }
+    });
+
+    let boxed = size_of_future(async {
+        selectme::select! {
+            boxed;
+
+            value = large(1) => value,
+            value = large(2) => value,
+        }
+    });
+
+    assert!(inline > 2 * 1024 * 4);
+    assert!(boxed < 1024);
+}
+
+/// Boxed selects behave like regular ones.
+#[selectme::test]
+async fn select_boxed() {
+    let output = selectme::select! {
+        boxed;
+        biased;
+
+        Some(value) = ready(None::<u32>) => value,
+        value = large(1), if true => value,
+        else => 0,
+    };
+
+    assert_eq!(output, 1024);
+}
+
+/// The branches of boxed inline selects can be accessed and reset.
+#[selectme::test]
+async fn select_boxed_inline() {
+    let output = selectme::inline! {
+        static;
+        boxed;
+
+        value = ready(1), if false => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next().await, None);
+    output.as_mut().set_branch::<0, _>(ready(2));
+    assert_eq!(output.as_mut().next().await, Some(2));
+
+    output.as_mut().reset(Box::pin((Slot::empty(),)));
+    assert_eq!(output.as_mut().next().await, None);
+    output.as_mut().set_branch::<0, _>(ready(3));
+    assert_eq!(output.as_mut().next().await, Some(3));
+    assert_eq!(output.as_mut().next().await, None);
+}
+
+/// Branches which have been constructed in the boxed state are dropped if
+/// constructing a later branch panics.
+#[test]
+fn select_boxed_construct_panic() {
+    fn explode() -> Ready<()> {
+        panic!("explode")
+    }
+
+    let log = RefCell::new(Vec::new());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _output = selectme::inline! {
+            boxed;
+
+            () = tracked("a", &log, pending::<()>()) => {}
+            () = tracked("b", &log, ready(())) => {}
+            () = explode() => {}
+            () = tracked("c", &log, ready(())) => {}
+        };
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(&*log.borrow(), &["a", "b"]);
+}
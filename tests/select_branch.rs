@@ -0,0 +1,72 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use selectme::SelectBranch;
+
+/// A branch which is ready a fixed number of times.
+struct Countdown(u32);
+
+impl SelectBranch for Countdown {
+    type Output = u32;
+
+    fn poll_branch(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+        self.0 -= 1;
+        Poll::Ready(self.0)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A branch which produces the given values once each, and re-arms itself
+/// while there are values remaining.
+struct Values(Vec<Option<u32>>);
+
+impl SelectBranch for Values {
+    type Output = Option<u32>;
+
+    fn poll_branch(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+        Poll::Ready(self.0.remove(0))
+    }
+
+    fn rearm(self: Pin<&mut Self>) -> bool {
+        !self.0.is_empty()
+    }
+}
+
+/// Branches which aren't finished are polled again.
+#[selectme::test]
+async fn select_branch_unfinished() {
+    let output = selectme::inline! {
+        n = Countdown(3) => Some(n),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut values = Vec::new();
+
+    while let Some(n) = output.as_mut().next().await {
+        values.push(n);
+    }
+
+    assert_eq!(values, [2, 1, 0]);
+}
+
+/// Branches whose pattern doesn't match are re-armed.
+#[selectme::test]
+async fn select_branch_rearm() {
+    let output = selectme::select! {
+        Some(n) = Values(vec![None, None, Some(3)]) => n,
+    };
+
+    assert_eq!(output, 3);
+
+    let output = selectme::select! {
+        Some(n) = Values(vec![None, None]) => n,
+        else => 0,
+    };
+
+    assert_eq!(output, 0);
+}
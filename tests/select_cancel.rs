@@ -0,0 +1,75 @@
+use std::future::{pending, ready};
+
+/// The cancellation branch is polled before any other branch, even if the
+/// select is biased.
+#[selectme::test]
+async fn select_cancel() {
+    let output = selectme::select! {
+        biased;
+        cancel = ready(());
+
+        value = ready(1) => Some(value),
+        cancelled => None,
+    };
+
+    assert_eq!(output, None);
+}
+
+/// Other branches complete as usual while the select isn't cancelled.
+#[selectme::test]
+async fn select_cancel_pending() {
+    let output = selectme::select! {
+        cancel = pending::<()>();
+
+        value = ready(1) => Some(value),
+        cancelled => None,
+    };
+
+    assert_eq!(output, Some(1));
+}
+
+/// The cancellation branch can break out of a loop.
+#[selectme::test]
+async fn select_cancel_loop() {
+    let mut count = 0;
+    let mut cancel = Some(());
+
+    loop {
+        selectme::select! {
+            cancel = async {
+                if count < 3 {
+                    pending::<()>().await;
+                }
+            };
+
+            () = ready(()) => {
+                count += 1;
+            }
+            cancelled => {
+                cancel.take();
+                break;
+            }
+        }
+    }
+
+    assert_eq!(count, 3);
+    assert!(cancel.is_none());
+}
+
+/// Inline selects are cancelled before polling any other branch.
+#[selectme::test]
+async fn inline_cancel() {
+    let output = selectme::inline! {
+        cancel = ready(());
+
+        value = ready(1) => Some(value),
+        value = ready(2) => Some(value),
+        cancelled => None,
+        else => Some(0),
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next().await, None);
+    assert_eq!(output.remaining(), 2);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "tokio")]
+
+use selectme::{Recv, UnboundedRecv};
+use tokio::sync::mpsc;
+
+/// Messages are not lost when receiving in a loop with cancel safe futures.
+#[tokio::test]
+async fn select_cancel_safe_recv() {
+    let (tx1, mut rx1) = mpsc::channel(8);
+    let (tx2, mut rx2) = mpsc::unbounded_channel();
+
+    for n in 0..4 {
+        tx1.send(n).await.unwrap();
+        tx2.send(n * 10).unwrap();
+    }
+
+    drop((tx1, tx2));
+
+    let mut values = Vec::new();
+    let mut open = (true, true);
+
+    loop {
+        selectme::select! {
+            cancel_safe;
+
+            value = Recv::new(&mut rx1), if open.0 => match value {
+                Some(value) => values.push(value),
+                None => open.0 = false,
+            },
+            value = UnboundedRecv::new(&mut rx2), if open.1 => match value {
+                Some(value) => values.push(value),
+                None => open.1 = false,
+            },
+            else => break,
+        }
+    }
+
+    values.sort();
+    assert_eq!(values, [0, 0, 1, 2, 3, 10, 20, 30]);
+}
+
+/// Send branches reserve permits through cancellation safe futures.
+#[tokio::test]
+async fn select_cancel_safe_send() {
+    let (tx, mut rx) = mpsc::channel(1);
+
+    for n in 0..3 {
+        selectme::select! {
+            cancel_safe;
+
+            send(&tx, n) => {}
+        }
+
+        assert_eq!(rx.recv().await, Some(n));
+
+        selectme::select! {
+            cancel_safe;
+
+            send(tx.clone(), n * 10) => {}
+        }
+
+        assert_eq!(rx.recv().await, Some(n * 10));
+    }
+
+    let output = selectme::inline! {
+        cancel_safe;
+
+        send(&tx, 3) => true,
+    };
+
+    assert!(output.await);
+    assert_eq!(rx.recv().await, Some(3));
+}
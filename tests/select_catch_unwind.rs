@@ -0,0 +1,48 @@
+use std::future::{pending, ready};
+
+async fn boom() -> u32 {
+    panic!("boom")
+}
+
+/// A panicking branch completes the select through the `panicked` arm.
+#[selectme::test]
+async fn select_catch_unwind() {
+    let output = selectme::select! {
+        catch_unwind;
+
+        value = boom() => value,
+        value = pending::<u32>() => value,
+        panicked(index, payload) => {
+            assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+            index * 100
+        }
+    };
+
+    assert_eq!(output, 0);
+}
+
+/// The panicking branch is disabled while the rest of the branches keep
+/// running.
+#[selectme::test]
+async fn select_catch_unwind_inline() {
+    let output = selectme::inline! {
+        catch_unwind;
+        biased;
+
+        value = ready(1) => Some(Ok(value)),
+        value = boom() => Some(Ok(value)),
+        value = ready(3) => Some(Ok(value)),
+        panicked(index, _) => Some(Err(index)),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut values = Vec::new();
+
+    while let Some(value) = output.as_mut().next().await {
+        values.push(value);
+    }
+
+    assert_eq!(values, [Ok(1), Err(1), Ok(3)]);
+}
@@ -0,0 +1,118 @@
+use std::cell::Cell;
+use std::future::{pending, ready};
+
+use selectme::Slot;
+
+/// Branches which are compiled out are never polled, and their expressions
+/// aren't compiled.
+#[selectme::test]
+async fn select_cfg_disabled() {
+    let output = selectme::select! {
+        biased;
+
+        #[cfg(any())]
+        value = does_not_exist() => value,
+        value = ready(2) => value,
+    };
+
+    assert_eq!(output, 2);
+}
+
+/// Branches whose predicates hold behave like any other branch.
+#[selectme::test]
+async fn select_cfg_enabled() {
+    let output = selectme::select! {
+        biased;
+
+        #[cfg(all())]
+        #[cfg(not(any()))]
+        value = ready(1) => value,
+        value = ready(2) => value,
+    };
+
+    assert_eq!(output, 1);
+}
+
+/// A branch which is compiled out counts as disabled.
+#[selectme::test]
+async fn select_cfg_else() {
+    let output = selectme::select! {
+        #[cfg(any())]
+        value = ready(1) => Some(value),
+        value = ready(2), if false => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, None);
+}
+
+/// Conditions of branches which are compiled out aren't evaluated.
+#[selectme::test]
+async fn select_cfg_condition() {
+    let evaluated = Cell::new(false);
+
+    let output = selectme::select! {
+        #[cfg(any())]
+        value = ready(1), if evaluated.replace(true) => Some(value),
+        #[cfg(all())]
+        value = ready(2), if !evaluated.get() => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, Some(2));
+    assert!(!evaluated.get());
+}
+
+/// Timers are only constructed if a branch which is compiled in uses them,
+/// so this compiles without the `tokio` feature.
+#[selectme::test]
+async fn select_cfg_timer() {
+    let output = selectme::select! {
+        #[cfg(any())]
+        after(std::time::Duration::from_secs(1)) => 1,
+        value = ready(2) => value,
+    };
+
+    assert_eq!(output, 2);
+}
+
+/// Static selects keep the branches which are compiled out disabled when
+/// they are reset.
+#[selectme::test]
+async fn select_cfg_static_reset() {
+    let output = selectme::inline! {
+        static;
+
+        #[cfg(any())]
+        value = does_not_exist() => Some(value),
+        value = ready(1) => Some(value),
+        () = pending::<()>(), if false => None,
+        else => None,
+    };
+
+    tokio::pin!(output);
+    assert_eq!(output.as_mut().next().await, Some(1));
+    assert_eq!(output.as_mut().next().await, None);
+
+    output.as_mut().reset(((), ready(2), Slot::empty()));
+    assert_eq!(output.as_mut().next().await, Some(2));
+    assert_eq!(output.as_mut().next().await, None);
+}
+
+/// Branches which are compiled out don't count towards a quorum.
+#[selectme::test]
+async fn select_cfg_quorum() {
+    let output = selectme::select! {
+        quorum = 2;
+
+        #[cfg(any())]
+        value = does_not_exist() => value,
+        value = ready(1) => value,
+        value = ready(2) => value,
+        else => Vec::new(),
+    };
+
+    let mut output = output;
+    output.sort();
+    assert_eq!(output, vec![(1, 1), (2, 2)]);
+}
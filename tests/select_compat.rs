@@ -0,0 +1,109 @@
+#![cfg(feature = "futures")]
+
+use std::future::{pending, ready, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::FusedFuture;
+
+/// A future which is terminated once it has completed.
+struct Fuse<F>(Option<F>);
+
+fn fuse<F>(future: F) -> Fuse<F> {
+    Fuse(Some(future))
+}
+
+impl<F> Future for Fuse<F>
+where
+    F: Future + Unpin,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let future = match &mut self.0 {
+            Some(future) => future,
+            None => return Poll::Pending,
+        };
+
+        let output = match Pin::new(future).poll(cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => return Poll::Pending,
+        };
+
+        self.0 = None;
+        Poll::Ready(output)
+    }
+}
+
+impl<F> FusedFuture for Fuse<F>
+where
+    F: Future + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// Terminated branches are disabled, so the `complete` arm is evaluated once
+/// all of them have completed.
+#[selectme::test]
+async fn select_compat_complete() {
+    let mut a = fuse(ready(1));
+    let mut b = fuse(ready(2));
+    let mut values = Vec::new();
+
+    loop {
+        selectme::futures_select! {
+            value = a => values.push(value),
+            value = b => values.push(value),
+            complete => break,
+        }
+    }
+
+    values.sort();
+    assert_eq!(values, [1, 2]);
+}
+
+/// The `default` arm is evaluated if no branch is ready.
+#[selectme::test]
+async fn select_compat_default() {
+    let mut a = fuse(pending::<u32>());
+    let mut b = fuse(ready(2));
+
+    let output = selectme::futures_select_biased! {
+        value = a => Some(value),
+        value = b => Some(value),
+        default => None,
+    };
+
+    assert_eq!(output, Some(2));
+
+    let output = selectme::select! {
+        compat(futures);
+
+        value = a => Some(value),
+        value = b => Some(value),
+        default => None,
+    };
+
+    assert_eq!(output, None);
+}
+
+/// The `complete` arm takes precedence over the `default` arm once every
+/// branch has terminated.
+#[selectme::test]
+async fn select_compat_complete_default() {
+    let mut a = fuse(ready(1));
+
+    let mut outputs = Vec::new();
+
+    for _ in 0..2 {
+        outputs.push(selectme::futures_select! {
+            value = a => value,
+            complete => 0,
+            default => u32::MAX,
+        });
+    }
+
+    assert_eq!(outputs, [1, 0]);
+}
@@ -0,0 +1,99 @@
+use std::future::{pending, ready};
+use std::time::Duration;
+
+use selectme::{MockTimer, Timer};
+
+use common::noop_waker;
+
+mod common;
+
+/// Disabled branches are not polled until they are enabled again.
+#[selectme::test]
+async fn select_disable_enable() {
+    let output = selectme::inline! {
+        biased;
+
+        value = ready(1) => Some(value),
+        value = ready(2) => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    output.as_mut().disable(0);
+    output.as_mut().disable(1);
+    assert_eq!(output.as_mut().next().await, None);
+
+    output.as_mut().enable(1);
+    assert_eq!(output.as_mut().next().await, Some(2));
+
+    output.as_mut().enable(0);
+    assert_eq!(output.as_mut().next().await, Some(1));
+    assert_eq!(output.as_mut().next().await, None);
+}
+
+/// Empty branch slots can be filled in after construction and be re-armed
+/// once they have completed.
+#[selectme::test]
+async fn select_set_branch() {
+    let timer = MockTimer::new();
+
+    let output = selectme::inline! {
+        static;
+        biased;
+
+        () = pending::<()>() => 0,
+        () = timer.sleep(Duration::ZERO), if false => 1,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+
+    for _ in 0..2 {
+        output
+            .as_mut()
+            .set_branch::<1, _>(timer.sleep(Duration::from_secs(1)));
+        assert!(output.as_mut().poll_next(&mut cx).is_pending());
+        timer.advance(Duration::from_secs(1));
+        assert_eq!(
+            output.as_mut().poll_next(&mut cx),
+            std::task::Poll::Ready(1)
+        );
+        assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    }
+}
+
+/// The state of a branch can be accessed and replaced in place.
+#[selectme::test]
+async fn select_branch_state() {
+    let output = selectme::inline! {
+        value = ready(1) => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    output.as_mut().branch::<0>().set(ready(2));
+    assert_eq!(output.as_mut().next().await, Some(2));
+
+    output.as_mut().branch::<0>().set(ready(3));
+    output.as_mut().enable(0);
+    assert_eq!(output.as_mut().next().await, Some(3));
+    assert_eq!(output.as_mut().next().await, None);
+}
+
+/// Enabling a branch outside of the mask panics.
+#[test]
+#[should_panic = "branch index out of bounds"]
+fn select_enable_out_of_bounds() {
+    let output = selectme::inline! {
+        value = ready(1) => value,
+    };
+
+    tokio::pin!(output);
+    output.as_mut().enable(8);
+}
@@ -0,0 +1,48 @@
+use std::cell::RefCell;
+use std::future::{pending, ready};
+
+use common::tracked;
+
+mod common;
+
+/// Branch futures are dropped in declaration order before the handler runs.
+#[selectme::test]
+async fn select_drop_before_handler() {
+    let log = RefCell::new(Vec::new());
+
+    selectme::select! {
+        biased;
+
+        () = tracked("a", &log, pending::<()>()) => {
+            log.borrow_mut().push("a handler");
+        }
+        () = tracked("b", &log, ready(())) => {
+            log.borrow_mut().push("b handler");
+        }
+        () = tracked("c", &log, pending::<()>()) => {
+            log.borrow_mut().push("c handler");
+        }
+    };
+
+    assert_eq!(&*log.borrow(), &["a", "b", "c", "b handler"]);
+}
+
+/// Branch futures are dropped before the `else` handler runs.
+#[selectme::test]
+async fn select_drop_before_else() {
+    let log = RefCell::new(Vec::new());
+
+    selectme::select! {
+        Some(()) = tracked("a", &log, ready(None)) => {
+            log.borrow_mut().push("a handler");
+        }
+        Some(()) = tracked("b", &log, ready(None)) => {
+            log.borrow_mut().push("b handler");
+        }
+        else => {
+            log.borrow_mut().push("else handler");
+        }
+    };
+
+    assert_eq!(&*log.borrow(), &["a", "b", "else handler"]);
+}
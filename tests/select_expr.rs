@@ -0,0 +1,197 @@
+use std::future::{ready, Ready};
+
+fn fetch<K, V>(value: V) -> Ready<(Option<K>, V)> {
+    ready((None, value))
+}
+
+trait Source {
+    fn source() -> Ready<u32>;
+}
+
+struct Fixed;
+
+impl Source for Fixed {
+    fn source() -> Ready<u32> {
+        ready(7)
+    }
+}
+
+/// Commas in turbofish generics are not mistaken for modifiers.
+#[selectme::test]
+async fn select_turbofish() {
+    let output = selectme::select! {
+        (_, value) = fetch::<(), u32>(1) => value,
+    };
+
+    assert_eq!(output, 1);
+
+    let output = selectme::select! {
+        Ok(value) = ready(Ok::<_, ()>(2)) => value,
+        else => 0,
+    };
+
+    assert_eq!(output, 2);
+
+    let output = selectme::select! {
+        value = ready::<Result<u32, ()>>(Ok(3)), if true => value,
+    };
+
+    assert_eq!(output, Ok(3));
+}
+
+/// Nested generics, qualified paths and casts are supported.
+#[selectme::test]
+async fn select_generics() {
+    let output = selectme::select! {
+        value = <Fixed as Source>::source() => value,
+    };
+
+    assert_eq!(output, 7);
+
+    let output = selectme::select! {
+        value = Box::pin(ready(8u32)) as std::pin::Pin<Box<dyn std::future::Future<Output = u32>>> => value,
+    };
+
+    assert_eq!(output, 8);
+
+    let output = selectme::select! {
+        (_, value) = fetch::<Vec<Vec<u32>>, fn(u32) -> u32>(|n| n + 1) => value(8),
+    };
+
+    assert_eq!(output, 9);
+}
+
+/// Generic arguments in conditions and modifiers.
+#[selectme::test]
+async fn select_generics_condition() {
+    let values = [1u32, 2, 3];
+
+    let output = selectme::select! {
+        value = ready(1), if values.iter().copied().sum::<u32>() < 10 => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, Some(1));
+
+    let output = selectme::select! {
+        value = ready(1), if values.len() < 2 => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, None);
+}
+
+/// `if` expressions can be used as branch expressions.
+#[selectme::test]
+async fn select_if_else() {
+    for (fast, expected) in [(true, 1), (false, 2)] {
+        let output = selectme::select! {
+            value = if fast { ready(1) } else { ready(2) } => value,
+        };
+
+        assert_eq!(output, expected);
+    }
+
+    for (n, expected) in [(0, 1), (1, 2), (2, 3)] {
+        let output = selectme::select! {
+            value = if n == 0 { ready(1) } else if n == 1 { ready(2) } else { ready(3) }, if n < 3 => value,
+            else => 0,
+        };
+
+        assert_eq!(output, expected);
+    }
+}
+
+/// `if` expressions can be used in conditions.
+#[selectme::test]
+async fn select_if_else_condition() {
+    let limit = Some(2);
+
+    let output = selectme::select! {
+        value = ready(1), if if let Some(limit) = limit { limit > 1 } else { false } => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, Some(1));
+}
+
+/// Comparisons in branch expressions aren't mistaken for generic arguments.
+#[selectme::test]
+async fn select_comparison() {
+    let (x, a, b) = (1u32, 2u32, 4u32);
+
+    let output = selectme::select! {
+        true = ready(x + a < b) => 1,
+        else => 0,
+    };
+
+    assert_eq!(output, 1);
+
+    let output = selectme::select! {
+        value = ready(x), if x + a < b => Some(value),
+        else => None,
+    };
+
+    assert_eq!(output, Some(1));
+
+    async fn checked(x: Option<u32>) -> Option<u32> {
+        selectme::select! {
+            value = ready(1), if x? < 2 => Some(value),
+            else => None,
+        }
+    }
+
+    assert_eq!(checked(Some(1)).await, Some(1));
+    assert_eq!(checked(Some(2)).await, None);
+    assert_eq!(checked(None).await, None);
+}
+
+/// Comparisons in `if` conditions aren't mistaken for generic arguments.
+#[selectme::test]
+async fn select_comparison_condition() {
+    for (n, m, expected) in [(1, 1, Some(1)), (0, 1, None), (1, 3, None)] {
+        let k = 2;
+
+        let output = selectme::select! {
+            value = ready(1), if n > 0 && m < k => Some(value),
+            else => None,
+        };
+
+        assert_eq!(output, expected);
+
+        let output = selectme::select! {
+            value = if n > 0 && m < k { ready(Some(1)) } else { ready(None) } => value,
+        };
+
+        assert_eq!(output, expected);
+    }
+}
+
+/// Option names can still be used as the binding of the first branch.
+#[selectme::test]
+async fn select_option_names_as_bindings() {
+    let output = selectme::select! {
+        timer = ready(1) => timer,
+    };
+
+    assert_eq!(output, 1);
+
+    let output = selectme::select! {
+        quorum = ready(2) => quorum,
+    };
+
+    assert_eq!(output, 2);
+
+    let output = selectme::select! {
+        cancel = ready(3) => cancel,
+    };
+
+    assert_eq!(output, 3);
+
+    let output = selectme::inline! {
+        timer = ready(4) => timer,
+        else => 0,
+    };
+
+    assert_eq!(output.await, 4);
+}
@@ -0,0 +1,78 @@
+use std::future::ready;
+
+/// Every branch which is ready in a single pass is returned in bias order.
+#[selectme::test]
+async fn select_next_batch() {
+    let output = selectme::inline! {
+        biased;
+
+        value = ready(1) => Some(value),
+        () = tokio::task::yield_now() => Some(2),
+        Some(value) = ready(None::<u32>) => Some(value),
+        value = ready(4) => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next_batch().await, [Some(1), Some(4)]);
+    assert_eq!(output.remaining(), 1);
+    assert_eq!(output.as_mut().next_batch().await, [Some(2)]);
+    assert!(output.is_terminated());
+    assert_eq!(output.as_mut().next_batch().await, [None]);
+}
+
+/// Static selects support batches, and pass the context to every handler.
+#[selectme::test]
+async fn static_select_next_batch() {
+    let output = selectme::inline! {
+        static;
+        biased;
+
+        value = ready(1) => Some(value),
+        value = ready(2) => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next_batch().await, [Some(1), Some(2)]);
+    assert_eq!(output.as_mut().next_batch().await, [None]);
+
+    let output = selectme::inline! {
+        static;
+        context: Vec<u32>;
+
+        value = ready(1) => context.push(value),
+        value = ready(2) => context.push(value),
+        else => (),
+    };
+
+    tokio::pin!(output);
+
+    let mut values = Vec::new();
+    let batch =
+        std::future::poll_fn(|cx| output.as_mut().poll_ready_batch_with(cx, &mut values)).await;
+
+    assert_eq!(batch.len(), 2);
+    values.sort();
+    assert_eq!(values, [1, 2]);
+}
+
+/// The order of a batch follows the bias of the selection.
+#[selectme::test]
+async fn select_next_batch_random() {
+    let output = selectme::inline! {
+        value = ready(1) => value,
+        value = ready(2) => value,
+        value = ready(3) => value,
+    };
+
+    tokio::pin!(output);
+
+    let mut values = output.as_mut().next_batch().await;
+    assert_eq!(values.len(), 3);
+    values.sort();
+    assert_eq!(values, [1, 2, 3]);
+    assert!(output.is_terminated());
+}
@@ -0,0 +1,248 @@
+use std::future::{pending, ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use selectme::{MockTimer, SelectBranch};
+
+/// A branch which is ready a fixed number of times.
+struct Countdown(u32);
+
+impl SelectBranch for Countdown {
+    type Output = u32;
+
+    fn poll_branch(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+        self.0 -= 1;
+        Poll::Ready(self.0)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// The select completes once a quorum of branches have completed, and the
+/// remaining branches are cancelled.
+#[selectme::test]
+async fn select_quorum() {
+    let mut acks = selectme::select! {
+        quorum = 2;
+
+        ack = ready(1) => ack,
+        ack = pending::<u32>() => ack,
+        ack = ready(3) => ack,
+        else => Vec::new(),
+    };
+
+    acks.sort();
+    assert_eq!(acks, [(0, 1), (2, 3)]);
+}
+
+/// The `else` arm is evaluated as soon as the quorum can no longer be
+/// reached.
+#[selectme::test]
+async fn select_quorum_else() {
+    let acks = selectme::select! {
+        quorum = 2;
+
+        Ok(ack) = ready(Ok::<u32, ()>(1)) => Some(ack),
+        Ok(ack) = ready(Err::<u32, ()>(())) => Some(ack),
+        ack = pending::<u32>(), if false => Some(ack),
+        else => vec![(0, None)],
+    };
+
+    assert_eq!(acks, [(0, None)]);
+}
+
+/// The `else` arm of a quorum can use control flow.
+#[selectme::test]
+async fn select_quorum_control() {
+    async fn acks(fail: bool) -> Option<usize> {
+        let acks = selectme::select! {
+            quorum = 2;
+
+            ack = ready(1u32) => ack,
+            false = ready(fail) => 2,
+            else => return None,
+        };
+
+        Some(acks.len())
+    }
+
+    assert_eq!(acks(false).await, Some(2));
+    assert_eq!(acks(true).await, None);
+}
+
+/// Handlers of a quorum run in the enclosing function, so they can await and
+/// return from it.
+#[selectme::test]
+async fn select_quorum_handler_control() {
+    async fn acks(fail: bool) -> Result<Vec<(u32, u32)>, &'static str> {
+        let acks = selectme::select! {
+            quorum = 3;
+
+            ack = ready(1u32) => ready(ack).await + 1,
+            ack = ready(fail) => {
+                if ack {
+                    return Err("failed");
+                }
+
+                2
+            },
+            ack = ready(Ok::<u32, &'static str>(3)) => ack?,
+        };
+
+        Ok(acks)
+    }
+
+    let mut acks_ok = acks(false).await.unwrap();
+    acks_ok.sort();
+    assert_eq!(acks_ok, [(0, 2), (1, 2), (2, 3)]);
+    assert_eq!(acks(true).await, Err("failed"));
+
+    async fn try_ack() -> Result<Vec<(u32, u32)>, &'static str> {
+        Ok(selectme::select! {
+            quorum = 1;
+
+            ack = ready(Err::<u32, &'static str>("error")) => ack?,
+        })
+    }
+
+    assert_eq!(try_ack().await, Err("error"));
+}
+
+/// Unlabeled `break` and `continue` in handlers of a quorum target the loop
+/// enclosing the select once every output has been handled, like `?` targets
+/// the enclosing function.
+#[selectme::test]
+async fn select_quorum_handler_loop() {
+    let mut rounds = Vec::new();
+
+    for round in 0..4u32 {
+        let acks = selectme::select! {
+            quorum = 2;
+
+            ack = ready(round) => if ack == 1 { continue } else { ack },
+            ack = ready(round * 10) => if ack == 20 { break } else { ack },
+        };
+
+        rounds.push(acks.len());
+    }
+
+    assert_eq!(rounds, [2]);
+
+    let mut n = 0u32;
+
+    let value = loop {
+        n += 1;
+
+        let _ = selectme::select! {
+            quorum = 1;
+
+            ack = ready(n) => if ack == 3 { break ack * 2 } else { ack },
+        };
+    };
+
+    assert_eq!(value, 6);
+
+    async fn nested(values: &[u32]) -> Result<u32, &'static str> {
+        let mut sum = 0;
+
+        for &value in values {
+            let acks = selectme::select! {
+                quorum = 1;
+
+                ack = ready(value) => {
+                    // Control flow in nested loops targets those loops.
+                    for n in 0.. {
+                        if n == ack {
+                            break;
+                        }
+                    }
+
+                    if ack == 0 {
+                        continue;
+                    }
+
+                    Ok::<_, &'static str>(ack).and_then(|ack| {
+                        if ack > 10 { Err("too large") } else { Ok(ack) }
+                    })?
+                },
+            };
+
+            sum += acks.iter().map(|(_, ack)| ack).sum::<u32>();
+        }
+
+        Ok(sum)
+    }
+
+    assert_eq!(nested(&[1, 0, 2]).await, Ok(3));
+    assert_eq!(nested(&[1, 11, 2]).await, Err("too large"));
+}
+
+/// The `timeout` arm of a branch doesn't count towards the quorum.
+#[selectme::test]
+async fn select_quorum_timeout() {
+    let timer = MockTimer::new();
+
+    let acks = selectme::select! {
+        timer = &timer;
+        quorum = 2;
+
+        ack = pending::<u32>(), timeout(Duration::ZERO) => ack,
+        timeout => 0,
+        ack = ready(1) => ack,
+        else => Vec::new(),
+    };
+
+    assert!(acks.is_empty());
+}
+
+/// A quorum of zero completes immediately.
+#[selectme::test]
+async fn select_quorum_zero() {
+    let acks = selectme::select! {
+        quorum = 0;
+
+        ack = pending::<u32>() => ack,
+    };
+
+    assert!(acks.is_empty());
+}
+
+/// Without an `else` arm it's a panic if the quorum can't be reached.
+#[selectme::test]
+#[should_panic = "quorum of branches can no longer be reached"]
+async fn select_quorum_unreachable() {
+    let _ = selectme::select! {
+        quorum = 2;
+
+        ack = ready(1) => ack,
+    };
+}
+
+/// A branch which completes multiple times only counts once towards the
+/// quorum.
+#[selectme::test]
+async fn select_quorum_repeated_branch() {
+    let acks = selectme::select! {
+        quorum = 2;
+        biased;
+
+        ack = Countdown(2) => ack,
+        ack = ready(10), if false => ack,
+        else => Vec::new(),
+    };
+
+    assert!(acks.is_empty());
+
+    let acks = selectme::select! {
+        quorum = 2;
+        biased;
+
+        ack = Countdown(2) => ack,
+        ack = ready(10) => ack,
+    };
+
+    assert_eq!(acks, [(0, 1), (0, 0), (1, 10)]);
+}
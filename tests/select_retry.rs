@@ -0,0 +1,82 @@
+use std::cell::Cell;
+use std::future::{pending, ready, Ready};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use selectme::MockTimer;
+
+use common::noop_waker;
+
+mod common;
+
+fn counter(count: &Cell<u32>) -> Ready<u32> {
+    let value = count.get() + 1;
+    count.set(value);
+    ready(value)
+}
+
+/// A retried branch rebuilds its future until its pattern matches.
+#[selectme::test]
+async fn select_retry() {
+    let count = Cell::new(0);
+
+    let output = selectme::select! {
+        3 = counter(&count), retry(5) => Some(count.get()),
+        else => None,
+    };
+
+    assert_eq!(output, Some(3));
+}
+
+/// A retried branch is disabled once it runs out of attempts.
+#[selectme::test]
+async fn select_retry_exhausted() {
+    let count = Cell::new(0);
+
+    let output = selectme::select! {
+        10 = counter(&count), retry(2), if true => Some(count.get()),
+        else => None,
+    };
+
+    assert_eq!(output, None);
+    assert_eq!(count.get(), 3);
+}
+
+/// A repeated branch is retried indefinitely.
+#[selectme::test]
+async fn select_repeat() {
+    let count = Cell::new(0);
+
+    let output = selectme::inline! {
+        100 = counter(&count), repeat => count.get(),
+    };
+
+    assert_eq!(output.await, 100);
+}
+
+/// Retried branches of an inline select can be delayed, in which case they
+/// keep their own clone of the timer.
+#[selectme::test]
+async fn inline_retry_start_after() {
+    let timer = MockTimer::new();
+    let count = Cell::new(0);
+
+    let output = selectme::inline! {
+        timer = timer.clone();
+
+        2 = counter(&count), start_after(Duration::from_secs(1)), retry(2) => Some(count.get()),
+        () = pending::<()>() => None,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(1));
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    assert_eq!(count.get(), 1);
+    timer.advance(Duration::from_secs(1));
+    assert_eq!(output.as_mut().poll_next(&mut cx), Poll::Ready(Some(2)));
+}
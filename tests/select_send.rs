@@ -0,0 +1,16 @@
+use std::future::{pending, ready};
+
+/// Selects asserting that their branches are `Send` can be spawned.
+#[tokio::test]
+async fn select_send_spawn() {
+    let task = tokio::spawn(async {
+        selectme::select! {
+            send;
+
+            value = ready(1) => value,
+            () = pending::<()>(), if false => 0,
+        }
+    });
+
+    assert_eq!(task.await.unwrap(), 1);
+}
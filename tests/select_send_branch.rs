@@ -0,0 +1,178 @@
+#![cfg(feature = "tokio")]
+
+use std::collections::VecDeque;
+use std::future::ready;
+
+use selectme::{SelectSend, SendPermit};
+use tokio::sync::mpsc;
+
+/// The value of a send branch is only evaluated if the branch wins.
+#[tokio::test]
+async fn select_send_branch_loses() {
+    let (tx, mut rx) = mpsc::channel(1);
+    tx.send(String::from("first")).await.unwrap();
+
+    let mut queue = VecDeque::from([String::from("second")]);
+
+    let output = selectme::select! {
+        biased;
+
+        send(&tx, queue.pop_front().unwrap()) => "sent",
+        () = ready(()) => "ready",
+    };
+
+    assert_eq!(output, "ready");
+    assert_eq!(queue.len(), 1);
+    assert_eq!(rx.recv().await.as_deref(), Some("first"));
+
+    let output = selectme::select! {
+        send(&tx, queue.pop_front().unwrap()) => "sent",
+    };
+
+    assert_eq!(output, "sent");
+    assert!(queue.is_empty());
+    assert_eq!(rx.recv().await.as_deref(), Some("second"));
+}
+
+/// Send branches are disabled if the channel is closed.
+#[tokio::test]
+async fn select_send_branch_closed() {
+    let (tx, rx) = mpsc::channel::<u32>(1);
+    drop(rx);
+
+    let output = selectme::select! {
+        send(&tx, 42) => "sent",
+        else => "closed",
+    };
+
+    assert_eq!(output, "closed");
+}
+
+/// Send branches can be used in inline selects.
+#[tokio::test]
+async fn select_send_branch_inline() {
+    let (tx, mut rx) = mpsc::channel(4);
+
+    let output = selectme::inline! {
+        send(&tx, 1), if true => Some(1),
+        send(&tx, 2) => Some(2),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut sent = Vec::new();
+
+    while let Some(value) = output.as_mut().next().await {
+        sent.push(value);
+    }
+
+    sent.sort();
+    assert_eq!(sent, [1, 2]);
+
+    let mut received = vec![rx.recv().await.unwrap(), rx.recv().await.unwrap()];
+    received.sort();
+    assert_eq!(received, [1, 2]);
+}
+
+/// Inline selects move the value into the branch, and hand it back if it
+/// hasn't been sent.
+#[tokio::test]
+async fn select_send_branch_inline_unsent() {
+    let (tx, mut rx) = mpsc::channel(1);
+    tx.send(String::from("first")).await.unwrap();
+
+    let message = String::from("second");
+
+    // NB: The select is dropped so that it gives up on its reservation.
+    {
+        let output = selectme::inline! {
+            biased;
+
+            send(&tx, message) => "sent",
+            () = ready(()) => "ready",
+        };
+
+        tokio::pin!(output);
+
+        assert_eq!(output.as_mut().next().await, "ready");
+
+        let unsent = output.as_mut().branch::<0>().take_value();
+        assert_eq!(unsent.as_deref(), Some("second"));
+    }
+
+    assert_eq!(rx.recv().await.as_deref(), Some("first"));
+
+    let message = String::from("third");
+
+    let output = selectme::inline! {
+        send(&tx, message) => "sent",
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next().await, "sent");
+    assert_eq!(output.as_mut().branch::<0>().take_value(), None);
+    assert_eq!(rx.recv().await.as_deref(), Some("third"));
+}
+
+/// The value of an inline send branch whose channel is closed can be taken
+/// back once the branch has been disabled.
+#[tokio::test]
+async fn select_send_branch_inline_closed() {
+    let (tx, rx) = mpsc::channel(1);
+    drop(rx);
+
+    let message = String::from("hello");
+
+    let output = selectme::inline! {
+        send(&tx, message) => Some(()),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().next().await, None);
+
+    let unsent = output.as_mut().branch::<0>().take_value();
+    assert_eq!(unsent.as_deref(), Some("hello"));
+}
+
+/// Commas in generic arguments aren't mistaken for argument separators.
+#[tokio::test]
+async fn select_send_branch_generics() {
+    let (tx, mut rx) = mpsc::channel(1);
+
+    selectme::select! {
+        send(&tx, Ok::<u32, ()>(1)) => {}
+    }
+
+    assert_eq!(rx.recv().await, Some(Ok(1)));
+}
+
+/// Tokio senders implement `SelectSend`, so they can be used through generic
+/// code, either borrowed or owned.
+#[tokio::test]
+async fn select_send_branch_trait() {
+    async fn send_or<S>(sender: S, value: <S::Permit as SendPermit>::Item) -> bool
+    where
+        S: SelectSend,
+    {
+        selectme::select! {
+            send(sender, value) => true,
+            else => false,
+        }
+    }
+
+    let (tx, mut rx) = mpsc::channel(1);
+
+    assert!(send_or(&tx, 1).await);
+    assert_eq!(rx.recv().await, Some(1));
+
+    assert!(send_or(tx.clone(), 2).await);
+    assert_eq!(rx.recv().await, Some(2));
+
+    drop(rx);
+    assert!(!send_or(&tx, 3).await);
+    assert!(!send_or(tx, 4).await);
+}
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+use std::future::{pending, ready, Future};
+use std::mem::size_of;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use selectme::Slot;
+
+use common::tracked;
+
+mod common;
+
+/// Slots don't store a discriminant.
+#[test]
+fn slot_size() {
+    assert_eq!(size_of::<Slot<[u64; 2]>>(), size_of::<[u64; 2]>());
+    assert!(size_of::<Slot<[u64; 2]>>() < size_of::<Option<[u64; 2]>>());
+}
+
+/// Only initialized slots are dropped, in declaration order.
+#[test]
+fn select_drop_initialized_slots() {
+    let log = RefCell::new(Vec::new());
+
+    let output = selectme::inline! {
+        () = tracked("a", &log, pending::<()>()), if false => (),
+        () = tracked("b", &log, pending::<()>()) => (),
+        () = tracked("c", &log, pending::<()>()), if true => (),
+        () = tracked("d", &log, pending::<()>()), if false => (),
+    };
+
+    assert!(log.borrow().is_empty());
+    drop(output);
+    assert_eq!(&*log.borrow(), &["b", "c"]);
+}
+
+/// Completed branches are kept in their slot until they are replaced or the
+/// select is dropped.
+#[selectme::test]
+async fn select_drop_completed_slot() {
+    let log = RefCell::new(Vec::new());
+
+    {
+        let output = selectme::inline! {
+            static;
+
+            () = tracked("a", &log, ready(())), if true => Some(()),
+            else => None,
+        };
+
+        tokio::pin!(output);
+
+        assert_eq!(output.as_mut().next().await, Some(()));
+        assert!(log.borrow().is_empty());
+
+        output
+            .as_mut()
+            .set_branch::<0, _>(tracked("b", &log, ready(())));
+        assert_eq!(&*log.borrow(), &["a"]);
+    }
+
+    assert_eq!(&*log.borrow(), &["a", "b"]);
+}
+
+/// Boxed state drops initialized slots as well.
+#[test]
+fn select_drop_boxed_slots() {
+    let log = RefCell::new(Vec::new());
+
+    let output = selectme::inline! {
+        boxed;
+
+        () = tracked("a", &log, pending::<()>()), if true => (),
+        () = tracked("b", &log, pending::<()>()), if false => (),
+        () = tracked("c", &log, pending::<()>()) => (),
+    };
+
+    drop(output);
+    assert_eq!(&*log.borrow(), &["a", "c"]);
+}
+
+/// A future which panics when it's dropped.
+struct PanicOnDrop;
+
+impl Future for PanicOnDrop {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        Poll::Pending
+    }
+}
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("dropped");
+    }
+}
+
+/// The remaining branches are still dropped if dropping one of them panics.
+#[test]
+fn select_drop_panicking_slot() {
+    let log = RefCell::new(Vec::new());
+
+    let output = selectme::inline! {
+        () = PanicOnDrop, if true => (),
+        () = tracked("a", &log, pending::<()>()) => (),
+        () = tracked("b", &log, pending::<()>()), if true => (),
+    };
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(output))).is_err());
+    assert_eq!(&*log.borrow(), &["a", "b"]);
+
+    let output = selectme::inline! {
+        boxed;
+
+        () = tracked("c", &log, pending::<()>()), if true => (),
+        () = PanicOnDrop => (),
+        () = tracked("d", &log, pending::<()>()), if true => (),
+    };
+
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(output))).is_err());
+    assert_eq!(&*log.borrow(), &["a", "b", "c", "d"]);
+}
@@ -0,0 +1,62 @@
+use std::cell::Cell;
+use std::future::{pending, ready};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use selectme::MockTimer;
+
+use common::noop_waker;
+
+mod common;
+
+/// A delayed branch is only started once its delay has elapsed.
+#[selectme::test]
+async fn select_start_after() {
+    let timer = MockTimer::new();
+    let started = Cell::new(false);
+
+    let backup = || {
+        started.set(true);
+        ready(2)
+    };
+
+    let output = selectme::inline! {
+        timer = timer.clone();
+
+        v = pending::<u32>() => v,
+        v = backup(), start_after(Duration::from_secs(5)) => v,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    assert!(!started.get());
+    timer.advance(Duration::from_secs(5));
+    assert_eq!(output.as_mut().poll_next(&mut cx), Poll::Ready(2));
+    assert!(started.get());
+}
+
+/// A delayed branch is never started if another branch completes first.
+#[selectme::test]
+async fn select_start_after_not_started() {
+    let timer = MockTimer::new();
+    let started = Cell::new(false);
+
+    let backup = || {
+        started.set(true);
+        ready(2)
+    };
+
+    let output = selectme::select! {
+        timer = &timer;
+
+        v = ready(1) => v,
+        v = backup(), start_after(Duration::from_secs(5)) => v,
+    };
+
+    assert_eq!(output, 1);
+    assert!(!started.get());
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "futures")]
+
+use selectme::StreamBranch;
+
+/// Every item of a stream is handed to the branch, followed by `None` once
+/// the stream has ended.
+#[selectme::test]
+async fn select_stream() {
+    let output = selectme::inline! {
+        item = StreamBranch::new(tokio_stream::iter([1, 2, 3])) => Some(item),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut items = Vec::new();
+
+    while let Some(item) = output.as_mut().next().await {
+        items.push(item);
+    }
+
+    assert_eq!(items, [Some(1), Some(2), Some(3), None]);
+}
+
+/// A borrowed stream can be polled in a loop, where the branch is disabled
+/// once the stream has ended.
+#[selectme::test]
+async fn select_stream_loop() {
+    let mut stream = tokio_stream::iter([1, 2, 3]);
+    let mut items = Vec::new();
+
+    loop {
+        selectme::select! {
+            Some(item) = StreamBranch::new(&mut stream) => items.push(item),
+            else => break,
+        }
+    }
+
+    assert_eq!(items, [1, 2, 3]);
+}
+
+/// Borrowed streams are cancellation safe, since their items are retained
+/// when the select completes.
+#[selectme::test]
+async fn select_stream_cancel_safe() {
+    let mut stream = tokio_stream::iter([1, 2, 3]);
+    let mut items = Vec::new();
+
+    loop {
+        selectme::select! {
+            cancel_safe;
+
+            Some(item) = StreamBranch::new(&mut stream) => items.push(item),
+            else => break,
+        }
+    }
+
+    assert_eq!(items, [1, 2, 3]);
+}
+
+/// Streams can be drained in batches.
+#[selectme::test]
+async fn select_stream_batch() {
+    let output = selectme::inline! {
+        items = StreamBranch::new(tokio_stream::iter([1, 2, 3, 4, 5])), batch(2) => Some(items),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    let mut batches = Vec::new();
+
+    while let Some(items) = output.as_mut().next().await {
+        batches.push(items);
+    }
+
+    assert_eq!(
+        batches,
+        [
+            vec![Some(1), Some(2)],
+            vec![Some(3), Some(4)],
+            vec![Some(5), None]
+        ]
+    );
+}
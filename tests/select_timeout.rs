@@ -0,0 +1,125 @@
+use std::future::{pending, ready};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use selectme::MockTimer;
+
+use common::noop_waker;
+
+mod common;
+
+/// A branch which times out is disabled while the other branches keep running.
+#[selectme::test]
+async fn select_timeout_disables_branch() {
+    let timer = MockTimer::new();
+
+    let output = selectme::inline! {
+        timer = timer.clone();
+
+        v = pending::<u32>(), timeout(Duration::from_secs(5)) => v,
+        after(Duration::from_secs(10)) => 2,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(5));
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(5));
+    assert_eq!(output.as_mut().poll_next(&mut cx), Poll::Ready(2));
+}
+
+/// A `timeout` arm completes the select when its branch times out.
+#[selectme::test]
+async fn select_timeout_arm() {
+    let timer = MockTimer::new();
+
+    let output = selectme::inline! {
+        timer = timer.clone();
+
+        v = pending::<u32>(), timeout(Duration::from_secs(5)) => v,
+        timeout => 1,
+        after(Duration::from_secs(10)) => 2,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(5));
+    assert_eq!(output.as_mut().poll_next(&mut cx), Poll::Ready(1));
+}
+
+/// `timeout` arms are evaluated like any other handler, so they can affect
+/// the control flow of the surrounding context.
+#[selectme::test]
+async fn select_timeout_control_flow() {
+    let timer = MockTimer::new();
+    let mut timeouts = 0;
+
+    let output = loop {
+        selectme::select! {
+            timer = &timer;
+
+            v = pending::<u32>(), timeout(Duration::ZERO) => break v,
+            timeout => {
+                timeouts += 1;
+
+                if timeouts < 3 {
+                    continue;
+                }
+
+                break 42;
+            }
+        }
+    };
+
+    assert_eq!(output, 42);
+    assert_eq!(timeouts, 3);
+
+    async fn fallible(timer: &MockTimer) -> Result<u32, &'static str> {
+        selectme::select! {
+            timer = timer;
+
+            v = pending::<u32>(), timeout(Duration::ZERO) => Ok(v),
+            timeout => Ok(Err::<u32, _>("timed out")?),
+        }
+    }
+
+    assert_eq!(fallible(&timer).await, Err("timed out"));
+}
+
+/// A branch which completes before its timeout is unaffected by it.
+#[selectme::test]
+async fn select_timeout_completes() {
+    let timer = MockTimer::new();
+
+    let output = selectme::select! {
+        timer = &timer;
+
+        v = ready(1), timeout(Duration::from_secs(5)) => v,
+    };
+
+    assert_eq!(output, 1);
+}
+
+/// If every branch times out, the `else` branch is evaluated.
+#[selectme::test]
+async fn select_timeout_else() {
+    let timer = MockTimer::new();
+
+    let output = selectme::select! {
+        timer = &timer;
+
+        v = pending::<u32>(), timeout(Duration::ZERO) => v,
+        v = pending::<u32>(), timeout(Duration::ZERO), if true => v,
+        else => 42,
+    };
+
+    assert_eq!(output, 42);
+}
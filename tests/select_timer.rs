@@ -0,0 +1,65 @@
+use std::future::{pending, ready};
+use std::time::Duration;
+
+use selectme::MockTimer;
+
+use common::noop_waker;
+
+mod common;
+
+/// A timer branch only completes once the timer has been advanced.
+#[selectme::test]
+async fn select_timer_after() {
+    let timer = MockTimer::new();
+
+    let output = selectme::inline! {
+        timer = timer.clone();
+
+        () = pending::<()>() => 1,
+        after(Duration::from_secs(5)) => 2,
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(4));
+    assert!(output.as_mut().poll_next(&mut cx).is_pending());
+    timer.advance(Duration::from_secs(1));
+    assert_eq!(
+        output.as_mut().poll_next(&mut cx),
+        std::task::Poll::Ready(2)
+    );
+}
+
+/// Timer branches support conditions and deadlines.
+#[selectme::test]
+async fn select_timer_at_condition() {
+    let timer = MockTimer::new();
+    timer.advance(Duration::from_secs(2));
+
+    let output = selectme::select! {
+        timer = &timer;
+
+        at(Duration::from_secs(1)), if false => 1,
+        at(Duration::from_secs(2)) if true => 2,
+        () = pending::<()>() => 3,
+    };
+
+    assert_eq!(output, 2);
+}
+
+/// Patterns which look like timer branches are still supported.
+#[selectme::test]
+async fn select_timer_pattern() {
+    #[allow(non_camel_case_types)]
+    struct after(u32);
+
+    let output = selectme::select! {
+        after(value) = ready(after(42)) => value,
+    };
+
+    assert_eq!(output, 42);
+}
@@ -0,0 +1,55 @@
+use std::future::ready;
+
+/// A selection without an `else` branch can be drained to exhaustion.
+#[selectme::test]
+async fn select_try_next_drain() {
+    let output = selectme::inline! {
+        biased;
+
+        value = ready(1) => value,
+        Some(value) = ready(None::<u32>) => value,
+        value = ready(3) => value,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.remaining(), 3);
+    assert_eq!(output.as_mut().try_next().await, Some(1));
+    assert_eq!(output.remaining(), 2);
+    assert_eq!(output.as_mut().try_next().await, Some(3));
+    assert!(output.is_terminated());
+    assert_eq!(output.as_mut().try_next().await, None);
+    assert_eq!(output.as_mut().try_next().await, None);
+}
+
+/// The `else` branch is not evaluated by `try_next`.
+#[selectme::test]
+async fn select_try_next_else() {
+    let output = selectme::inline! {
+        static;
+
+        value = ready(1) => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    assert_eq!(output.as_mut().try_next().await, Some(Some(1)));
+    assert_eq!(output.as_mut().try_next().await, None);
+    assert_eq!(output.as_mut().next().await, None);
+}
+
+/// A selection where all branches are disabled up front terminates
+/// immediately.
+#[selectme::test]
+async fn select_try_next_disabled() {
+    let output = selectme::inline! {
+        value = ready(1), if false => value,
+    };
+
+    tokio::pin!(output);
+
+    assert!(output.is_terminated());
+    assert_eq!(output.remaining(), 0);
+    assert_eq!(output.as_mut().try_next().await, None);
+}
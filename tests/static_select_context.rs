@@ -0,0 +1,100 @@
+use std::future::{pending, ready, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project::pin_project;
+use selectme::{Poller, StaticSelect, Unbiased};
+
+use common::noop_waker;
+
+mod common;
+
+#[derive(Default)]
+struct Counters {
+    first: u32,
+    second: u32,
+}
+
+#[pin_project]
+struct Counting<S, P> {
+    counters: Counters,
+    #[pin]
+    select: StaticSelect<u8, S, Unbiased, bool, P>,
+}
+
+impl<S, P> Future for Counting<S, P>
+where
+    P: Poller<u8, S, Counters, Output = bool>,
+{
+    type Output = (u32, u32);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        while let Poll::Ready(more) = this.select.as_mut().poll_next_with(cx, this.counters) {
+            if !more {
+                return Poll::Ready((this.counters.first, this.counters.second));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Handlers of a static select can update the fields of a parent future
+/// through the context.
+#[selectme::test]
+async fn static_select_context_parent() {
+    let future = Counting {
+        counters: Counters::default(),
+        select: selectme::inline! {
+            static;
+            biased;
+            context: Counters;
+
+            value = ready(2) => {
+                context.first += value;
+                true
+            }
+            value = ready(3) => {
+                context.second += value;
+                true
+            }
+            else => {
+                context.first *= 10;
+                false
+            }
+        },
+    };
+
+    assert_eq!(future.await, (20, 3));
+}
+
+/// The context is passed by mutable reference to the conditional branches and
+/// is available in pattern handlers.
+#[selectme::test]
+async fn static_select_context_pending() {
+    let output = selectme::inline! {
+        static;
+        context: Vec<u32>;
+
+        Some(value) = ready(Some(1)) => context.push(value),
+        () = pending::<()>() => context.clear(),
+    };
+
+    tokio::pin!(output);
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut values = Vec::new();
+
+    assert!(output
+        .as_mut()
+        .poll_next_with(&mut cx, &mut values)
+        .is_ready());
+    assert!(output
+        .as_mut()
+        .poll_next_with(&mut cx, &mut values)
+        .is_pending());
+    assert_eq!(values, &[1]);
+}
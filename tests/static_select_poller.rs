@@ -0,0 +1,45 @@
+use std::future::{ready, Ready};
+use std::mem::size_of;
+
+use selectme::{StaticSelect, Unbiased};
+
+fn poller_size<Bits, S, B, O, P>(_: &StaticSelect<Bits, S, B, O, P>) -> usize {
+    size_of::<P>()
+}
+
+/// The poller generated for a static select is zero-sized.
+#[selectme::test]
+async fn static_select_zero_sized_poller() {
+    let output = selectme::inline! {
+        static;
+        biased;
+
+        value = ready(1) => Some(value),
+        value = ready(2) => Some(value),
+        else => None,
+    };
+
+    assert_eq!(poller_size(&output), 0);
+    assert_eq!(output.await, Some(1));
+}
+
+/// The generated poller is coerced into a function pointer when the select is
+/// named through its output.
+#[selectme::test]
+async fn static_select_poll_fn() {
+    type State = (Ready<u32>,);
+
+    let output: StaticSelect<u8, State, Unbiased, Option<u32>> = selectme::inline! {
+        static;
+        biased;
+
+        value = ready(1) => Some(value),
+        else => None,
+    };
+
+    assert_eq!(poller_size(&output), size_of::<usize>());
+
+    tokio::pin!(output);
+    assert_eq!(output.as_mut().next().await, Some(1));
+    assert_eq!(output.as_mut().next().await, None);
+}
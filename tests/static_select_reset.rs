@@ -0,0 +1,93 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project::pin_project;
+use selectme::{Slot, StaticSelect, Unbiased};
+
+type State = (Ready<u32>, Slot<Ready<u32>>);
+
+#[pin_project]
+struct Summing {
+    rounds: u32,
+    sum: u32,
+    #[pin]
+    select: StaticSelect<u8, State, Unbiased, Option<u32>>,
+}
+
+impl Future for Summing {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            match this.select.as_mut().poll_next(cx) {
+                Poll::Ready(Some(value)) => {
+                    *this.sum += value;
+                }
+                Poll::Ready(None) => {
+                    if *this.rounds == 0 {
+                        return Poll::Ready(*this.sum);
+                    }
+
+                    *this.rounds -= 1;
+                    this.select
+                        .as_mut()
+                        .reset((ready(*this.rounds), Slot::empty()));
+
+                    if *this.rounds % 2 == 0 {
+                        this.select.as_mut().set_branch::<1, _>(ready(100));
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A single select can be reused by resetting it with a new state.
+#[selectme::test]
+async fn static_select_reset_parent() {
+    let future = Summing {
+        rounds: 4,
+        sum: 0,
+        select: selectme::inline! {
+            static;
+            biased;
+
+            value = ready(1) => Some(value),
+            value = ready(0), if false => Some(value),
+            else => None,
+        },
+    };
+
+    // 1 from the initial state, then 3 + 2 + 1 + 0 from the reset rounds and
+    // 100 from the two rounds which populated the conditional branch.
+    assert_eq!(future.await, 1 + 3 + 2 + 1 + 200);
+}
+
+/// Resetting a select only enables unconditional branches, conditional ones
+/// are enabled once they're populated.
+#[selectme::test]
+async fn static_select_reset_empty_slot() {
+    let output = selectme::inline! {
+        static;
+
+        value = ready(1), if true => Some(value),
+        value = ready(2), if true => Some(value),
+        else => None,
+    };
+
+    tokio::pin!(output);
+
+    output.as_mut().reset((Slot::empty(), Slot::empty()));
+    assert_eq!(output.remaining(), 0);
+    assert!(output.is_terminated());
+
+    output.as_mut().set_branch::<1, _>(ready(3));
+    assert_eq!(output.remaining(), 1);
+    assert_eq!(output.as_mut().next().await, Some(3));
+    assert_eq!(output.as_mut().next().await, None);
+    assert!(output.is_terminated());
+}
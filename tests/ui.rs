@@ -4,4 +4,8 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.pass("tests/ui/*_pass.rs");
     t.compile_fail("tests/ui/*_fail.rs");
+
+    // NB: These depend on options which require the `futures` feature.
+    #[cfg(feature = "futures")]
+    t.compile_fail("tests/ui/futures/*_fail.rs");
 }
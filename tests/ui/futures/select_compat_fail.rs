@@ -0,0 +1,40 @@
+pub(crate) fn main() {
+}
+
+/// The `complete` and `default` arms require the `compat(futures)` option.
+async fn error_arms_without_compat() {
+    selectme::select! {
+        () = async {} => {},
+        complete => {},
+        default => {},
+    };
+}
+
+/// Only compatibility with `futures` is supported.
+async fn error_compat_unsupported() {
+    selectme::select! {
+        compat(tokio);
+
+        () = async {} => {},
+    };
+}
+
+/// The `complete` arm can't be used together with an `else` arm.
+async fn error_complete_else() {
+    selectme::select! {
+        compat(futures);
+
+        () = async {} => {},
+        complete => {},
+        else => {},
+    };
+}
+
+/// The `compat` option is only supported by `select!`.
+async fn error_compat_inline() {
+    let _ = selectme::inline! {
+        compat(futures);
+
+        () = async {} => {},
+    };
+}
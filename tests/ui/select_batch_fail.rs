@@ -0,0 +1,17 @@
+pub(crate) fn main() {
+}
+
+/// A batch limit of zero is rejected.
+async fn error_batch_zero() {
+    let _ = selectme::select! {
+        items = async { 1 }, batch(0) => items,
+    };
+}
+
+
+/// A suffixed batch limit of zero is rejected.
+async fn error_batch_zero_suffixed() {
+    let _ = selectme::select! {
+        items = async { 1 }, batch(0_usize) => items,
+    };
+}
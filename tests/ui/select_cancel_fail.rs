@@ -0,0 +1,41 @@
+pub(crate) fn main() {
+}
+
+/// The `cancelled` arm requires the `cancel` option.
+async fn error_cancelled_without_option() {
+    selectme::select! {
+        () = async {} => {},
+        cancelled => {},
+    };
+}
+
+/// The `cancel` option requires a `cancelled` arm.
+async fn error_cancel_without_arm() {
+    selectme::select! {
+        cancel = async {};
+
+        () = async {} => {},
+    };
+}
+
+/// The `cancelled` arm may only be specified once.
+async fn error_cancelled_duplicate() {
+    selectme::select! {
+        cancel = async {};
+
+        () = async {} => {},
+        cancelled => {},
+        cancelled => {},
+    };
+}
+
+/// The `cancel` option can't be used together with a quorum.
+async fn error_cancel_quorum() {
+    let _ = selectme::select! {
+        quorum = 1;
+        cancel = async {};
+
+        () = async {} => {},
+        cancelled => Vec::new(),
+    };
+}
@@ -0,0 +1,24 @@
+use std::future::ready;
+
+pub(crate) fn main() {
+}
+
+/// Branches which aren't cancellation safe are reported on their expression.
+async fn error_branch_not_cancel_safe() {
+    selectme::select! {
+        cancel_safe;
+
+        _ = ready(1) => {},
+        _ = async { 1 } => {},
+    };
+}
+
+/// The `cancel_safe` option can only be specified once.
+async fn error_multiple_cancel_safe() {
+    selectme::select! {
+        cancel_safe;
+        cancel_safe;
+
+        _ = ready(1) => {},
+    };
+}
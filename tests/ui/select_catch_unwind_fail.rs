@@ -0,0 +1,42 @@
+use std::future::ready;
+
+pub(crate) fn main() {
+}
+
+/// The `panicked` arm requires the `catch_unwind` option.
+async fn error_panicked_without_catch_unwind() {
+    selectme::select! {
+        _ = ready(1) => {},
+        panicked(index, payload) => {},
+    };
+}
+
+/// The `catch_unwind` option requires a `panicked` arm.
+async fn error_catch_unwind_without_panicked() {
+    selectme::select! {
+        catch_unwind;
+
+        _ = ready(1) => {},
+    };
+}
+
+/// The `panicked` arm binds exactly two patterns.
+async fn error_panicked_patterns() {
+    selectme::select! {
+        catch_unwind;
+
+        _ = ready(1) => {},
+        panicked(payload) => {},
+    };
+}
+
+/// The `panicked` arm can only be specified once.
+async fn error_multiple_panicked() {
+    selectme::select! {
+        catch_unwind;
+
+        _ = ready(1) => {},
+        panicked(_, _) => {},
+        panicked(_, _) => {},
+    };
+}
@@ -0,0 +1,27 @@
+pub(crate) fn main() {
+}
+
+/// Only `#[cfg(..)]` attributes are supported on branches.
+async fn error_unsupported_attribute() {
+    selectme::select! {
+        #[allow(unused)]
+        () = async {} => {},
+    };
+}
+
+/// The `#[cfg(..)]` attribute requires a predicate.
+async fn error_empty_predicate() {
+    selectme::select! {
+        #[cfg()]
+        () = async {} => {},
+    };
+}
+
+/// Arms which aren't branches can't be compiled out.
+async fn error_cfg_else() {
+    selectme::select! {
+        () = async {} => {},
+        #[cfg(any())]
+        else => {},
+    };
+}
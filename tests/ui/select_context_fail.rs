@@ -0,0 +1,26 @@
+pub(crate) fn main() {
+}
+
+/// The `context` option requires the `static` option.
+async fn error_context_not_static() {
+    selectme::inline! {
+        context: u32;
+    };
+}
+
+/// The `context` option requires a type.
+async fn error_context_missing_type() {
+    selectme::inline! {
+        static;
+        context: ;
+    };
+}
+
+/// Context can only be specified once.
+async fn error_multiple_context() {
+    selectme::inline! {
+        static;
+        context: u32;
+        context: u32;
+    };
+}
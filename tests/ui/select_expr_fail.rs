@@ -0,0 +1,24 @@
+pub(crate) fn main() {
+}
+
+/// The condition of an `if` expression must be followed by a block.
+async fn error_if_without_block() {
+    selectme::select! {
+        value = if true => value,
+    };
+}
+
+/// An `else` must be followed by a block or another `if`.
+async fn error_else_without_block() {
+    selectme::select! {
+        value = if true { async { 1 } } else async { 2 } => value,
+    };
+}
+
+/// Errors in `if` expressions don't prevent later branches from being parsed.
+async fn error_recovery() {
+    selectme::select! {
+        value = if true => value,
+        value = async { 1 } => value,
+    };
+}
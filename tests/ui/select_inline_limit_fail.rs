@@ -0,0 +1,25 @@
+pub(crate) fn main() {
+}
+
+/// The state of `inline!` can only be accessed for up to 16 branches.
+async fn error_inline_too_many_branches() {
+    selectme::inline! {
+        v = std::future::ready(0) => v,
+        v = std::future::ready(1) => v,
+        v = std::future::ready(2) => v,
+        v = std::future::ready(3) => v,
+        v = std::future::ready(4) => v,
+        v = std::future::ready(5) => v,
+        v = std::future::ready(6) => v,
+        v = std::future::ready(7) => v,
+        v = std::future::ready(8) => v,
+        v = std::future::ready(9) => v,
+        v = std::future::ready(10) => v,
+        v = std::future::ready(11) => v,
+        v = std::future::ready(12) => v,
+        v = std::future::ready(13) => v,
+        v = std::future::ready(14) => v,
+        v = std::future::ready(15) => v,
+        v = std::future::ready(16) => v,
+    };
+}
@@ -0,0 +1,32 @@
+pub(crate) fn main() {
+}
+
+/// The quorum option is only supported by `select!`.
+async fn error_quorum_inline() {
+    let _ = selectme::inline! {
+        quorum = 1;
+
+        ack = async { 1 } => ack,
+    };
+}
+
+/// The quorum option can't be used together with `catch_unwind`.
+async fn error_quorum_catch_unwind() {
+    let _ = selectme::select! {
+        quorum = 1;
+        catch_unwind;
+
+        ack = async { 1 } => ack,
+        panicked(_, _) => Vec::new(),
+    };
+}
+
+/// The quorum option requires a value.
+async fn error_quorum_empty() {
+    let _ = selectme::select! {
+        quorum = ;
+
+        ack = async { 1 } => ack,
+    };
+}
+
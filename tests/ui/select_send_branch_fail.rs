@@ -0,0 +1,20 @@
+pub(crate) fn main() {
+}
+
+/// Send branches require both a sender and a value.
+async fn error_send_missing_value() {
+    let tx = ();
+
+    selectme::select! {
+        send(&tx) => {},
+    };
+}
+
+/// Send branches can't be batched.
+async fn error_send_batch() {
+    let tx = ();
+
+    selectme::select! {
+        send(&tx, 1), batch(4) => {},
+    };
+}
@@ -0,0 +1,27 @@
+use std::future::ready;
+use std::rc::Rc;
+
+pub(crate) fn main() {
+}
+
+/// Branches which aren't `Send` are reported on their expression.
+async fn error_branch_not_send() {
+    let value = Rc::new(1);
+
+    selectme::select! {
+        send;
+
+        _ = ready(1) => {},
+        _ = ready(value) => {},
+    };
+}
+
+/// The `send` option can only be specified once.
+async fn error_multiple_send() {
+    selectme::select! {
+        send;
+        send;
+
+        _ = ready(1) => {},
+    };
+}
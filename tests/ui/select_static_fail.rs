@@ -15,3 +15,17 @@ async fn error_multiple_static() {
         static;
     };
 }
+
+/// Handlers of a static select can't capture their environment.
+async fn error_static_capture() {
+    let captured = 41;
+
+    let output = selectme::inline! {
+        static;
+
+        v = std::future::ready(1) => Some(v + captured),
+        else => None,
+    };
+
+    output.await;
+}
@@ -0,0 +1,11 @@
+pub(crate) fn main() {
+}
+
+/// The `timer` option must be used by a branch.
+async fn error_unused_timer() {
+    let _ = selectme::select! {
+        timer = ();
+
+        value = async { 1 } => value,
+    };
+}